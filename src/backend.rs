@@ -1,10 +1,316 @@
-use crate::parser::DocumentationData;
+use crate::markup::ReferenceKind;
+use crate::parser::{DocTags, DocumentationData, EntryType, FunctionArgument, SourceSpan};
 
 use std::fs::File;
+use std::path::{Component, Path, PathBuf};
+
+pub struct Page {
+    // Path of the source file, relative to the input directory
+    pub path: PathBuf,
+    pub resource_path: String,
+    pub class_name: Option<String>,
+    // The source_url_template with everything but the line filled in
+    pub source_url: Option<String>,
+}
+
+impl Page {
+    pub fn new(path: &Path, resource_path: String, class_name: Option<String>) -> Page {
+        Page {
+            path: path
+                .components()
+                .filter(|c| c != &Component::CurDir)
+                .collect(),
+            resource_path,
+            class_name,
+            source_url: None,
+        }
+    }
+
+    pub fn output_path(&self, extension: &str) -> PathBuf {
+        PathBuf::from(format!("{}.{}", self.path.display(), extension))
+    }
+
+    pub fn source_link(&self, span: &SourceSpan) -> Option<String> {
+        self.source_url
+            .as_ref()
+            .map(|url| url.replace("{line}", &span.line.to_string()))
+    }
+
+    // Link to the generated file, from a page with the given root prefix
+    pub fn link(&self, root: &str, extension: &str) -> String {
+        let path = self
+            .output_path(extension)
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().to_string())
+            .collect::<Vec<_>>()
+            .join("/");
+        format!("{}{}", root, path)
+    }
+}
+
+// Prefix leading from a page to the root of the output directory
+pub fn root_prefix(path: &Path) -> String {
+    "../".repeat(path.components().count() - 1)
+}
+
+pub fn class_page(pages: &[Page], root: &str, class_name: &str, extension: &str) -> Option<String> {
+    pages
+        .iter()
+        .find(|p| p.class_name.as_deref() == Some(class_name))
+        .map(|p| p.link(root, extension))
+}
+
+// Kind of a symbol in the anchors of the backends linking to symbols
+pub fn anchor_kind(entry_type: EntryType) -> &'static str {
+    match entry_type {
+        EntryType::CLASS => "class",
+        EntryType::SIGNAL => "signal",
+        EntryType::FUNC => "method",
+        EntryType::VAR | EntryType::EXPORT => "member",
+        EntryType::CONST => "constant",
+        EntryType::ENUM => "enum",
+    }
+}
+
+fn reference_kind(kind: ReferenceKind) -> &'static str {
+    match kind {
+        ReferenceKind::Class => "class",
+        ReferenceKind::Method => "method",
+        ReferenceKind::Member => "member",
+        ReferenceKind::Signal => "signal",
+        ReferenceKind::Constant => "constant",
+        ReferenceKind::Enum => "enum",
+        ReferenceKind::Annotation => "annotation",
+        ReferenceKind::Param => "param",
+    }
+}
+
+// Link to the target of a reference in a doc comment, if it can be linked
+pub fn reference_link(
+    pages: &[Page],
+    root: &str,
+    kind: ReferenceKind,
+    target: &str,
+    extension: &str,
+) -> Option<String> {
+    match kind {
+        ReferenceKind::Class => class_page(pages, root, target, extension),
+        ReferenceKind::Annotation | ReferenceKind::Param => None,
+        _ => match target.rfind('.') {
+            Some(pos) => {
+                let (class, member) = (&target[..pos], &target[pos + 1..]);
+                let anchor = format!("{}-{}", reference_kind(kind), member);
+                match class_page(pages, root, class, extension) {
+                    Some(page) => Some(format!("{}#{}", page, anchor)),
+                    None => Some(format!("#{}.{}", class, anchor)),
+                }
+            }
+            None => Some(format!("#{}-{}", reference_kind(kind), target)),
+        },
+    }
+}
+
+// Notices shown above the description of an entry, as class, title and text
+pub fn notices(tags: &DocTags) -> Vec<(&'static str, &'static str, &str)> {
+    [
+        ("deprecated", "Deprecated", &tags.deprecated),
+        ("experimental", "Experimental", &tags.experimental),
+        ("since", "Since", &tags.since),
+    ]
+    .iter()
+    .filter_map(|&(class, title, text)| text.as_deref().map(|text| (class, title, text)))
+    .collect()
+}
+
+// The arguments of a function along with the text of their @param tag, empty if no parameter
+// is documented
+pub fn parameters<'a>(
+    tags: &'a DocTags,
+    arguments: &'a [FunctionArgument],
+) -> Vec<(&'a FunctionArgument, Option<&'a str>)> {
+    if tags.params.is_empty() {
+        return Vec::new();
+    }
+
+    arguments
+        .iter()
+        .map(|argument| {
+            let text = tags
+                .params
+                .iter()
+                .find(|p| p.name == argument.name)
+                .map(|p| p.text.as_str());
+            (argument, text)
+        })
+        .collect()
+}
+
+// Whether a link from a doc comment may be followed: http, https and mailto URLs or relative ones,
+// other schemes like javascript: could run code in the reader's browser
+pub fn is_safe_url(url: &str) -> bool {
+    // Browsers drop tabs and newlines within URLs and leading spaces
+    if url.chars().any(|c| c.is_control()) {
+        return false;
+    }
+
+    let url = url.trim_start();
+    match url.find([':', '/', '?', '#']) {
+        Some(pos) if url[pos..].starts_with(':') => {
+            let scheme = url[..pos].to_ascii_lowercase();
+            matches!(scheme.as_str(), "http" | "https" | "mailto")
+        }
+        _ => true,
+    }
+}
 
 pub trait Backend {
-    fn generate_output(&self, data: DocumentationData, f: &mut File) -> std::io::Result<()>;
+    fn generate_output(
+        &self,
+        data: DocumentationData,
+        page: &Page,
+        pages: &[Page],
+        // The page is written to f, backends generating further files put them in output_dir
+        output_dir: &Path,
+        f: &mut File,
+    ) -> std::io::Result<()>;
     fn get_extension(&self) -> String;
+
+    // Called after all pages have been generated, for output spanning the whole project
+    fn generate_index(&self, _pages: &[Page], _output_path: &Path) -> std::io::Result<()> {
+        Ok(())
+    }
 }
 
+pub mod godotxmlbackend;
+pub mod htmlbackend;
+pub mod jsonbackend;
 pub mod markdownbackend;
+pub mod rstbackend;
+
+// A new directory for a test, tests run in parallel and each of them writes files of its own
+#[cfg(test)]
+fn test_dir() -> PathBuf {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static TESTS: AtomicUsize = AtomicUsize::new(0);
+    let dir = std::env::temp_dir().join(format!(
+        "godotdoc-test-{}-{}",
+        std::process::id(),
+        TESTS.fetch_add(1, Ordering::Relaxed)
+    ));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Parses a script given as a string the way the script files of a project are parsed, for the
+/// tests of the modules working with its documentation.
+#[cfg(test)]
+pub fn parse_script(source: &str) -> (DocumentationData, Vec<crate::diagnostic::Diagnostic>) {
+    let dir = test_dir();
+    let path = dir.join("test.gd");
+    std::fs::write(&path, source).unwrap();
+
+    let settings = crate::Settings {
+        backend: Box::new(jsonbackend::JsonBackend::new()),
+        output_path: Path::new(""),
+        excluded_files: Vec::new(),
+        show_prefixed: true,
+        source_url_template: None,
+        source_prefix: String::new(),
+    };
+    let mut diagnostics = Vec::new();
+    let f = File::open(&path).unwrap();
+    let data = crate::parser::parse_file(Path::new("test.gd"), f, &settings, &mut diagnostics);
+    std::fs::remove_dir_all(&dir).unwrap();
+    (data.unwrap(), diagnostics)
+}
+
+/// Parses the scripts of a project, given by their paths and sources, for the tests.
+#[cfg(test)]
+pub fn project_files(scripts: &[(&str, &str)]) -> Vec<crate::project::ProjectFile> {
+    scripts
+        .iter()
+        .map(|(path, source)| {
+            let (data, _) = parse_script(source);
+            crate::project::ProjectFile::new(PathBuf::from(path), "res://", data)
+        })
+        .collect()
+}
+
+/// Generates the page of a script given as a string with the backend, for the tests of the
+/// backends. The script is documented on the first of the pages.
+#[cfg(test)]
+pub fn render(backend: &dyn Backend, source: &str, pages: &[Page]) -> String {
+    let dir = test_dir();
+    let (data, _) = parse_script(source);
+    let path = dir.join("page");
+    let mut f = File::create(&path).unwrap();
+    backend
+        .generate_output(data, &pages[0], pages, &dir, &mut f)
+        .unwrap();
+    let output = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::ParamDoc;
+
+    #[test]
+    fn safe_urls() {
+        for url in [
+            "https://godotengine.org",
+            "HTTP://a.b",
+            "mailto:a@b.c",
+            "docs/page.html",
+        ] {
+            assert!(is_safe_url(url), "{}", url);
+        }
+        for url in ["../a.html", "#anchor", "?a=b:c", "a/b:c"] {
+            assert!(is_safe_url(url), "{}", url);
+        }
+        for url in [
+            "javascript:alert(1)",
+            " JavaScript:x",
+            "java\tscript:x",
+            "data:text/html,x",
+        ] {
+            assert!(!is_safe_url(url), "{}", url);
+        }
+    }
+
+    #[test]
+    fn notices_and_parameters() {
+        let tags = DocTags {
+            params: vec![ParamDoc {
+                name: "b".to_string(),
+                text: "the second".to_string(),
+            }],
+            deprecated: Some(String::new()),
+            since: Some("1.2".to_string()),
+            ..DocTags::default()
+        };
+        assert_eq!(
+            notices(&tags),
+            [("deprecated", "Deprecated", ""), ("since", "Since", "1.2")]
+        );
+
+        let arguments = ["a", "b"]
+            .iter()
+            .map(|name| FunctionArgument {
+                name: name.to_string(),
+                value_type: None,
+                default_value: None,
+                span: SourceSpan::default(),
+            })
+            .collect::<Vec<_>>();
+        let documented = parameters(&tags, &arguments)
+            .into_iter()
+            .map(|(argument, text)| (argument.name.as_str(), text))
+            .collect::<Vec<_>>();
+        assert_eq!(documented, [("a", None), ("b", Some("the second"))]);
+        assert!(parameters(&DocTags::default(), &arguments).is_empty());
+    }
+}
@@ -0,0 +1,435 @@
+use crate::backend::{Backend, Page};
+use crate::parser::VariableArgStruct;
+use crate::parser::{ClassArgStruct, DocTags, DocumentationData, DocumentationEntry, EntryType};
+use crate::parser::{ExportArgStruct, FunctionArgStruct, FunctionArgument, Symbol, SymbolArgs};
+
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+pub struct GodotXmlBackend {}
+
+impl GodotXmlBackend {
+    pub fn new() -> GodotXmlBackend {
+        GodotXmlBackend {}
+    }
+}
+
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn attribute(name: &str, value: &str) -> String {
+    format!(" {}=\"{}\"", name, escape_xml(value))
+}
+
+fn tag_attributes(tags: &DocTags) -> String {
+    let mut s = String::new();
+    if let Some(deprecated) = &tags.deprecated {
+        s += &attribute("deprecated", deprecated);
+    }
+    if let Some(experimental) = &tags.experimental {
+        s += &attribute("experimental", experimental);
+    }
+    s
+}
+
+// Brackets in plain comments are escaped, so Godot doesn't take them for BBCode tags
+fn comment_lines(text: &[String], doc_comment: bool) -> Vec<String> {
+    if doc_comment {
+        return text.to_vec();
+    }
+
+    text.iter()
+        .map(|line| {
+            line.chars()
+                .map(|c| match c {
+                    '[' => "[lb]".to_string(),
+                    ']' => "[rb]".to_string(),
+                    c => c.to_string(),
+                })
+                .collect()
+        })
+        .collect()
+}
+
+// Doc comments already use Godot's BBCode markup, the documented tags without a
+// counterpart in the class reference are appended to the description
+fn description_lines(text: &[String], doc_comment: bool, tags: &DocTags) -> Vec<String> {
+    let mut lines = comment_lines(text, doc_comment);
+
+    let mut extra = Vec::new();
+    for param in &tags.params {
+        extra.push(format!("[param {}]: {}", param.name, param.text));
+    }
+    if let Some(returns) = &tags.returns {
+        extra.push(format!("[b]Returns:[/b] {}", returns));
+    }
+    if let Some(since) = &tags.since {
+        extra.push(format!("[b]Since:[/b] {}", since));
+    }
+
+    if !extra.is_empty() && !lines.is_empty() {
+        lines.push(String::new());
+    }
+    lines.extend(extra);
+    lines
+}
+
+fn write_text(s: &mut String, indent: usize, lines: &[String]) {
+    for line in lines {
+        if line.is_empty() {
+            s.push('\n');
+        } else {
+            *s += &format!("{}{}\n", "\t".repeat(indent), escape_xml(line));
+        }
+    }
+}
+
+fn write_description(s: &mut String, indent: usize, tag: &str, lines: &[String]) {
+    let tabs = "\t".repeat(indent);
+    *s += &format!("{}<{}>\n", tabs, tag);
+    write_text(s, indent + 1, lines);
+    *s += &format!("{}</{}>\n", tabs, tag);
+}
+
+// Untyped and inferred declarations ("var x := 1") are documented as Variant
+fn type_name(value_type: &Option<String>) -> &str {
+    match value_type.as_deref() {
+        Some("") | None => "Variant",
+        Some(value_type) => value_type,
+    }
+}
+
+fn write_params(s: &mut String, arguments: &[FunctionArgument]) {
+    for (index, argument) in arguments.iter().enumerate() {
+        *s += &format!(
+            "\t\t\t<param index=\"{}\"{}{}",
+            index,
+            attribute("name", &argument.name),
+            attribute("type", type_name(&argument.value_type))
+        );
+        if let Some(default_value) = &argument.default_value {
+            *s += &attribute("default", default_value);
+        }
+        *s += " />\n";
+    }
+}
+
+struct ClassData<'a> {
+    name: String,
+    // Name of the class relative to its script, empty for the script itself
+    inner_name: String,
+    inherits: Option<&'a str>,
+    text: &'a [String],
+    doc_comment: bool,
+    tags: &'a DocTags,
+    entries: &'a [DocumentationEntry],
+}
+
+fn symbols<'a>(
+    entries: &'a [DocumentationEntry],
+    types: &[EntryType],
+) -> Vec<(EntryType, &'a Symbol)> {
+    entries
+        .iter()
+        .filter(|e| types.contains(&e.entry_type))
+        .flat_map(|e| e.symbols.iter().map(move |s| (e.entry_type, s)))
+        .collect()
+}
+
+fn generate_class(class: ClassData, inner_classes: &mut Vec<(String, String)>) -> String {
+    let mut s = "<?xml version=\"1.0\" encoding=\"UTF-8\" ?>\n".to_string();
+    s += &format!("<class{}", attribute("name", &class.name));
+    if let Some(inherits) = class.inherits {
+        s += &attribute("inherits", inherits);
+    }
+    s += &tag_attributes(class.tags);
+    s += ">\n";
+
+    // The first paragraph of the comment serves as brief description
+    let text = description_lines(class.text, class.doc_comment, class.tags);
+    let brief_end = text.iter().position(|l| l.is_empty()).unwrap_or(text.len());
+    write_description(&mut s, 1, "brief_description", &text[..brief_end]);
+    write_description(
+        &mut s,
+        1,
+        "description",
+        text.get(brief_end + 1..).unwrap_or(&[]),
+    );
+    s += "\t<tutorials>\n\t</tutorials>\n";
+
+    let methods = symbols(class.entries, &[EntryType::FUNC]);
+    if !methods.is_empty() {
+        s += "\t<methods>\n";
+        for (_, symbol) in methods {
+            let mut return_type = "Variant".to_string();
+            let mut arguments: &[FunctionArgument] = &[];
+            if let Some(SymbolArgs::FunctionArgs(FunctionArgStruct {
+                arguments: args,
+                return_type: ret,
+                ..
+            })) = &symbol.arg
+            {
+                arguments = args;
+                if let Some(ret) = ret {
+                    return_type = ret.clone();
+                }
+            }
+            let attributes = attribute("name", &symbol.name) + &tag_attributes(&symbol.tags);
+
+            s += &format!("\t\t<method{}>\n", attributes);
+            s += &format!("\t\t\t<return{} />\n", attribute("type", &return_type));
+            write_params(&mut s, arguments);
+            write_description(
+                &mut s,
+                3,
+                "description",
+                &description_lines(&symbol.text, symbol.doc_comment, &symbol.tags),
+            );
+            s += "\t\t</method>\n";
+        }
+        s += "\t</methods>\n";
+    }
+
+    let members = symbols(class.entries, &[EntryType::VAR, EntryType::EXPORT]);
+    if !members.is_empty() {
+        s += "\t<members>\n";
+        for (_, symbol) in members {
+            let (value_type, assignment, setter, getter) = match &symbol.arg {
+                Some(SymbolArgs::VariableArgs(VariableArgStruct {
+                    value_type,
+                    assignment,
+                    setter,
+                    getter,
+                    ..
+                }))
+                | Some(SymbolArgs::ExportArgs(ExportArgStruct {
+                    value_type,
+                    assignment,
+                    setter,
+                    getter,
+                    ..
+                })) => (value_type, assignment, setter, getter),
+                _ => (&None, &None, &None, &None),
+            };
+
+            s += &format!(
+                "\t\t<member{}{}",
+                attribute("name", &symbol.name),
+                attribute("type", type_name(value_type))
+            );
+            s += &attribute("setter", setter.as_deref().unwrap_or(""));
+            s += &attribute("getter", getter.as_deref().unwrap_or(""));
+            if let Some(assignment) = assignment {
+                s += &attribute("default", assignment);
+            }
+            s += &tag_attributes(&symbol.tags);
+            s += ">\n";
+            write_text(
+                &mut s,
+                3,
+                &description_lines(&symbol.text, symbol.doc_comment, &symbol.tags),
+            );
+            s += "\t\t</member>\n";
+        }
+        s += "\t</members>\n";
+    }
+
+    let signals = symbols(class.entries, &[EntryType::SIGNAL]);
+    if !signals.is_empty() {
+        s += "\t<signals>\n";
+        for (_, symbol) in signals {
+            s += &format!(
+                "\t\t<signal{}{}>\n",
+                attribute("name", &symbol.name),
+                tag_attributes(&symbol.tags)
+            );
+            if let Some(SymbolArgs::SignalArgs(arguments)) = &symbol.arg {
+                write_params(&mut s, arguments);
+            }
+            write_description(
+                &mut s,
+                3,
+                "description",
+                &description_lines(&symbol.text, symbol.doc_comment, &symbol.tags),
+            );
+            s += "\t\t</signal>\n";
+        }
+        s += "\t</signals>\n";
+    }
+
+    let constants = symbols(class.entries, &[EntryType::CONST, EntryType::ENUM]);
+    if !constants.is_empty() {
+        s += "\t<constants>\n";
+        for (entry_type, symbol) in constants {
+            match (entry_type, &symbol.arg) {
+                (EntryType::ENUM, Some(SymbolArgs::EnumArgs(values))) => {
+                    for value in values {
+                        s += &format!(
+                            "\t\t<constant{}{}{}>\n",
+                            attribute("name", &value.name),
+                            attribute("value", &value.value.to_string()),
+                            attribute("enum", &symbol.name)
+                        );
+                        write_text(&mut s, 3, &comment_lines(&value.text, value.doc_comment));
+                        s += "\t\t</constant>\n";
+                    }
+                }
+                _ => {
+                    let value = match &symbol.arg {
+                        Some(SymbolArgs::VariableArgs(VariableArgStruct {
+                            assignment: Some(assignment),
+                            ..
+                        })) => assignment.as_str(),
+                        _ => "",
+                    };
+                    s += &format!(
+                        "\t\t<constant{}{}{}>\n",
+                        attribute("name", &symbol.name),
+                        attribute("value", value),
+                        tag_attributes(&symbol.tags)
+                    );
+                    write_text(
+                        &mut s,
+                        3,
+                        &description_lines(&symbol.text, symbol.doc_comment, &symbol.tags),
+                    );
+                    s += "\t\t</constant>\n";
+                }
+            }
+        }
+        s += "\t</constants>\n";
+    }
+
+    s += "</class>\n";
+
+    for (_, symbol) in symbols(class.entries, &[EntryType::CLASS]) {
+        if let Some(SymbolArgs::ClassArgs(ClassArgStruct {
+            extends, entries, ..
+        })) = &symbol.arg
+        {
+            let inner_name = if class.inner_name.is_empty() {
+                symbol.name.clone()
+            } else {
+                format!("{}.{}", class.inner_name, symbol.name)
+            };
+            let inner = ClassData {
+                name: format!("{}.{}", class.name, symbol.name),
+                inner_name: inner_name.clone(),
+                inherits: extends.as_deref(),
+                text: &symbol.text,
+                doc_comment: symbol.doc_comment,
+                tags: &symbol.tags,
+                entries,
+            };
+            let xml = generate_class(inner, inner_classes);
+            inner_classes.push((inner_name, xml));
+        }
+    }
+
+    s
+}
+
+impl Backend for GodotXmlBackend {
+    fn get_extension(&self) -> String {
+        "xml".to_string()
+    }
+
+    fn generate_output(
+        &self,
+        data: DocumentationData,
+        page: &Page,
+        _pages: &[Page],
+        output_dir: &Path,
+        f: &mut File,
+    ) -> std::io::Result<()> {
+        // Godot names the documentation of scripts without class_name after their path
+        let name = data
+            .class_name
+            .clone()
+            .unwrap_or_else(|| format!("\"{}\"", page.resource_path));
+        let class = ClassData {
+            name,
+            inner_name: String::new(),
+            inherits: data.extends.as_deref(),
+            text: &data.text,
+            doc_comment: data.doc_comment,
+            tags: &data.tags,
+            entries: &data.entries,
+        };
+
+        let mut inner_classes = Vec::new();
+        write!(f, "{}", generate_class(class, &mut inner_classes))?;
+
+        // The class reference format holds a single class per file, inner classes are written
+        // next to the file of their script
+        let page_path = page.path.display().to_string();
+        for (name, xml) in inner_classes {
+            let path = output_dir.join(format!("{}.{}.xml", page_path, name));
+            std::fs::write(path, xml)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::parse_script;
+
+    fn generate(source: &str) -> (String, Vec<(String, String)>) {
+        let (data, _) = parse_script(source);
+        let class = ClassData {
+            name: "Test".to_string(),
+            inner_name: String::new(),
+            inherits: data.extends.as_deref(),
+            text: &data.text,
+            doc_comment: data.doc_comment,
+            tags: &data.tags,
+            entries: &data.entries,
+        };
+        let mut inner_classes = Vec::new();
+        let xml = generate_class(class, &mut inner_classes);
+        (xml, inner_classes)
+    }
+
+    #[test]
+    fn script_description() {
+        let (xml, _) = generate("## Brief.\n##\n## Long [b]text[/b].\nextends Node\n");
+        assert!(xml.contains("<class name=\"Test\" inherits=\"Node\">"));
+        assert!(xml.contains("<brief_description>\n\t\tBrief.\n\t</brief_description>"));
+        assert!(xml.contains("<description>\n\t\tLong [b]text[/b].\n\t</description>"));
+    }
+
+    #[test]
+    fn engine_callbacks_are_plain_methods() {
+        let (xml, _) = generate("extends Node\nfunc _ready():\n\tpass\n");
+        assert!(xml.contains("<method name=\"_ready\">"));
+        assert!(!xml.contains("qualifiers"));
+    }
+
+    #[test]
+    fn inner_classes() {
+        let (xml, inner_classes) = generate("class A:\n\tclass B:\n\t\tvar x\n\tvar y\nvar z\n");
+        assert!(!xml.contains("name=\"A\""));
+        let names = inner_classes
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["A.B", "A"]);
+        assert!(inner_classes[0].1.contains("<class name=\"Test.A.B\">"));
+        assert!(inner_classes[0].1.contains("<member name=\"x\""));
+    }
+
+    #[test]
+    fn plain_comments_escape_brackets() {
+        let (xml, _) = generate("# See [b]\nvar x\n");
+        assert!(xml.contains("See [lb]b[rb]"));
+    }
+}
@@ -0,0 +1,831 @@
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::backend::{anchor_kind, class_page, is_safe_url, notices, parameters, reference_link};
+use crate::backend::{root_prefix, Backend, Page};
+use crate::markup::{parse_markup, Markup, ReferenceKind};
+use crate::parser::{ClassArgStruct, DocumentationData, DocumentationEntry, InheritedMembers};
+use crate::parser::{DocTags, EntryType, FunctionArgument, InspectorSection, SourceSpan, Symbol};
+use crate::parser::{ExportArgStruct, FunctionArgStruct, SymbolArgs, VariableArgStruct};
+
+// HTML has no headings below <h6>, deeper nested classes share its level
+const MAX_HEADING: usize = 6;
+
+const STYLESHEET: &str = "body {
+    display: flex;
+    margin: 0;
+    font-family: sans-serif;
+    line-height: 1.5;
+    color: #222;
+}
+
+nav.sidebar {
+    flex: 0 0 16em;
+    min-height: 100vh;
+    padding: 1em;
+    background: #f3f3f3;
+    border-right: 1px solid #ddd;
+}
+
+nav.sidebar ul {
+    list-style: none;
+    padding-left: 1em;
+    margin: 0;
+}
+
+nav.sidebar > ul {
+    padding-left: 0;
+}
+
+nav.sidebar .current > a {
+    font-weight: bold;
+}
+
+nav.sidebar .directory {
+    color: #666;
+}
+
+main {
+    flex: 1;
+    max-width: 60em;
+    padding: 1em 2em;
+}
+
+a {
+    color: #2a6ebb;
+    text-decoration: none;
+}
+
+a:hover {
+    text-decoration: underline;
+}
+
+.symbol {
+    margin: 1em 0;
+    padding-left: 1em;
+    border-left: 3px solid #ddd;
+}
+
+.symbol .anchor {
+    visibility: hidden;
+    font-size: 0.8em;
+}
+
+.symbol :hover > .anchor {
+    visibility: visible;
+}
+
+.symbol .source {
+    float: right;
+    font-size: 0.8em;
+    font-weight: normal;
+}
+
+.inspector-section {
+    margin: 1em 0 0.5em;
+    font-weight: bold;
+}
+
+.inspector-section.category {
+    padding: 0.2em 0.5em;
+    background: #e8e8e8;
+    text-align: center;
+}
+
+.inspector-section.subgroup {
+    padding-left: 1em;
+}
+
+.notice {
+    margin: 0.2em 0;
+    font-style: italic;
+}
+
+.notice.deprecated {
+    color: #b03030;
+}
+
+dl.properties {
+    display: grid;
+    grid-template-columns: max-content auto;
+    gap: 0 1em;
+    margin: 0.5em 0;
+}
+
+dl.properties dt {
+    font-weight: bold;
+}
+
+dl.properties dd {
+    margin: 0;
+}
+
+table {
+    border-collapse: collapse;
+    margin: 0.5em 0;
+}
+
+th, td {
+    padding: 0.2em 0.8em;
+    border: 1px solid #ddd;
+    text-align: left;
+}
+
+code, pre {
+    font-family: monospace;
+    background: #f6f6f6;
+}
+
+pre {
+    padding: 0.5em;
+    overflow-x: auto;
+}
+";
+
+pub struct HtmlBackend {}
+
+impl HtmlBackend {
+    pub fn new() -> HtmlBackend {
+        HtmlBackend {}
+    }
+}
+
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn join<T: ToString>(v: &[T], s: &str) -> String {
+    v.iter()
+        .map(|x| escape_html(&x.to_string()))
+        .collect::<Vec<_>>()
+        .join(s)
+}
+
+struct Context<'a> {
+    root: String,
+    // The page being generated, None for the index
+    page: Option<&'a Page>,
+    pages: &'a [Page],
+}
+
+impl<'a> Context<'a> {
+    fn class_page(&self, class_name: &str) -> Option<String> {
+        class_page(self.pages, &self.root, class_name, "html")
+    }
+
+    fn format_source(&self, span: &SourceSpan) -> String {
+        match self.page.and_then(|page| page.source_link(span)) {
+            Some(link) => format!(
+                " <a class=\"source\" href=\"{}\">source</a>",
+                escape_html(&link)
+            ),
+            None => String::new(),
+        }
+    }
+
+    fn format_reference(&self, kind: ReferenceKind, target: &str) -> String {
+        let label = match kind {
+            ReferenceKind::Method => format!("<code>{}()</code>", escape_html(target)),
+            _ => format!("<code>{}</code>", escape_html(target)),
+        };
+
+        let link = reference_link(self.pages, &self.root, kind, target, "html");
+
+        match link {
+            Some(link) => format!("<a href=\"{}\">{}</a>", escape_html(&link), label),
+            None => label,
+        }
+    }
+
+    fn format_markup(&self, nodes: Vec<Markup>) -> String {
+        let mut s = String::new();
+
+        for node in nodes {
+            match node {
+                Markup::Text(text) => s += &escape_html(&text),
+                Markup::LineBreak => s += "<br>\n",
+                Markup::Bold(children) => s += &format!("<b>{}</b>", self.format_markup(children)),
+                Markup::Italic(children) => {
+                    s += &format!("<i>{}</i>", self.format_markup(children))
+                }
+                Markup::Code(code) => s += &format!("<code>{}</code>", escape_html(&code)),
+                Markup::CodeBlock(code) => {
+                    s += &format!("<pre><code>{}</code></pre>\n", escape_html(&code))
+                }
+                Markup::Reference(kind, target) => s += &self.format_reference(kind, &target),
+                Markup::Link(url, children) if is_safe_url(&url) => {
+                    s += &format!(
+                        "<a href=\"{}\">{}</a>",
+                        escape_html(&url),
+                        self.format_markup(children)
+                    )
+                }
+                Markup::Link(_, children) => s += &self.format_markup(children),
+            }
+        }
+
+        s
+    }
+
+    fn format_comments(&self, text: Vec<String>, doc_comment: bool) -> String {
+        if text.is_empty() {
+            return String::new();
+        }
+
+        format!(
+            "<div class=\"description\">{}</div>\n",
+            self.format_markup(parse_markup(&text, doc_comment))
+        )
+    }
+
+    fn write_sidebar(&self, f: &mut File) -> std::io::Result<()> {
+        enum Node<'a> {
+            Directory(BTreeMap<String, Node<'a>>),
+            Page(&'a Page),
+        }
+
+        fn write_nodes(
+            context: &Context,
+            nodes: &BTreeMap<String, Node>,
+            current: Option<&Page>,
+            f: &mut File,
+        ) -> std::io::Result<()> {
+            writeln!(f, "<ul>")?;
+            for (name, node) in nodes {
+                match node {
+                    Node::Directory(children) => {
+                        writeln!(
+                            f,
+                            "<li><span class=\"directory\">{}/</span>",
+                            escape_html(name)
+                        )?;
+                        write_nodes(context, children, current, f)?;
+                        writeln!(f, "</li>")?;
+                    }
+                    Node::Page(page) => {
+                        let class = match current {
+                            Some(current) if current.path == page.path => " class=\"current\"",
+                            _ => "",
+                        };
+                        writeln!(
+                            f,
+                            "<li{}><a href=\"{}\">{}</a></li>",
+                            class,
+                            escape_html(&page.link(&context.root, "html")),
+                            escape_html(name)
+                        )?;
+                    }
+                }
+            }
+            writeln!(f, "</ul>")
+        }
+
+        let mut tree = BTreeMap::new();
+        for page in self.pages {
+            let mut nodes = &mut tree;
+            let components = page
+                .path
+                .iter()
+                .map(|c| c.to_string_lossy().to_string())
+                .collect::<Vec<_>>();
+            let (file_name, directories) = components.split_last().unwrap();
+
+            for directory in directories {
+                let node = nodes
+                    .entry(directory.clone())
+                    .or_insert_with(|| Node::Directory(BTreeMap::new()));
+                nodes = match node {
+                    Node::Directory(children) => children,
+                    Node::Page(_) => unreachable!(),
+                };
+            }
+            nodes.insert(file_name.clone(), Node::Page(page));
+        }
+
+        writeln!(f, "<nav class=\"sidebar\">")?;
+        writeln!(
+            f,
+            "<p><a href=\"{}index.html\">Index</a></p>",
+            escape_html(&self.root)
+        )?;
+        write_nodes(self, &tree, self.page, f)?;
+        writeln!(f, "</nav>")
+    }
+
+    fn write_header(&self, title: &str, f: &mut File) -> std::io::Result<()> {
+        writeln!(f, "<!DOCTYPE html>")?;
+        writeln!(f, "<html>")?;
+        writeln!(f, "<head>")?;
+        writeln!(f, "<meta charset=\"utf-8\">")?;
+        writeln!(f, "<title>{}</title>", escape_html(title))?;
+        writeln!(
+            f,
+            "<link rel=\"stylesheet\" href=\"{}style.css\">",
+            escape_html(&self.root)
+        )?;
+        writeln!(f, "</head>")?;
+        writeln!(f, "<body>")?;
+        self.write_sidebar(f)?;
+        writeln!(f, "<main>")
+    }
+}
+
+fn write_footer(f: &mut File) -> std::io::Result<()> {
+    writeln!(f, "</main>")?;
+    writeln!(f, "</body>")?;
+    writeln!(f, "</html>")
+}
+
+fn format_notices(tags: &DocTags) -> String {
+    let mut s = String::new();
+
+    for (class, title, text) in notices(tags) {
+        s += &format!("<p class=\"notice {}\"><b>{}</b>", class, title);
+        if !text.is_empty() {
+            s += &format!(": {}", escape_html(text));
+        }
+        s += "</p>\n";
+    }
+
+    s
+}
+
+fn format_details(tags: &DocTags, arguments: &[FunctionArgument]) -> String {
+    let mut details = String::new();
+
+    let parameters = parameters(tags, arguments);
+    if !parameters.is_empty() {
+        details += "<table class=\"parameters\">\n";
+        details += "<tr><th>Name</th><th>Type</th><th>Default</th><th>Description</th></tr>\n";
+        for (argument, text) in parameters {
+            details += &format!(
+                "<tr><td><code>{}</code></td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                escape_html(&argument.name),
+                escape_html(argument.value_type.as_deref().unwrap_or("")),
+                escape_html(argument.default_value.as_deref().unwrap_or("")),
+                escape_html(text.unwrap_or(""))
+            );
+        }
+        details += "</table>\n";
+    }
+    if let Some(returns) = &tags.returns {
+        details += &format!("<p><b>Returns</b>: {}</p>\n", escape_html(returns));
+    }
+
+    details
+}
+
+fn format_properties(properties: Vec<(&str, String)>) -> String {
+    if properties.is_empty() {
+        return String::new();
+    }
+
+    let mut s = "<dl class=\"properties\">\n".to_string();
+    for (title, value) in properties {
+        s += &format!("<dt>{}</dt><dd>{}</dd>\n", title, value);
+    }
+    s + "</dl>\n"
+}
+
+fn write_inherited(
+    context: &Context,
+    level: usize,
+    inherited: Vec<InheritedMembers>,
+    f: &mut File,
+) -> std::io::Result<()> {
+    let level = level.min(MAX_HEADING);
+    for members in inherited {
+        let page = context.class_page(&members.ancestor);
+        let ancestor = match &page {
+            Some(page) => format!(
+                "<a href=\"{}\">{}</a>",
+                escape_html(page),
+                escape_html(&members.ancestor)
+            ),
+            None => escape_html(&members.ancestor),
+        };
+        writeln!(f, "<h{}>Inherited from {}</h{}>", level, ancestor, level)?;
+
+        writeln!(f, "<dl class=\"properties\">")?;
+        for entry in members.entries {
+            let names = entry
+                .names
+                .iter()
+                .map(|name| match &page {
+                    Some(page) => format!(
+                        "<a href=\"{}#{}-{}\"><code>{}</code></a>",
+                        escape_html(page),
+                        anchor_kind(entry.entry_type),
+                        escape_html(name),
+                        escape_html(name)
+                    ),
+                    None => format!("<code>{}</code>", escape_html(name)),
+                })
+                .collect::<Vec<_>>();
+            writeln!(
+                f,
+                "<dt>{}</dt><dd>{}</dd>",
+                entry.entry_type,
+                names.join(", ")
+            )?;
+        }
+        writeln!(f, "</dl>")?;
+    }
+
+    Ok(())
+}
+
+fn write_symbol(
+    context: &Context,
+    scope: &str,
+    level: usize,
+    entry_type: EntryType,
+    symbol: Symbol,
+    f: &mut File,
+) -> std::io::Result<()> {
+    let level = level.min(MAX_HEADING);
+    let anchor = format!("{}{}-{}", scope, anchor_kind(entry_type), symbol.name);
+    let name = escape_html(&symbol.name);
+    let mut signature = name.clone();
+    let mut properties = Vec::new();
+    let mut details = format_details(&symbol.tags, &[]);
+    let mut body = String::new();
+
+    if let Some(args) = symbol.arg {
+        match args {
+            SymbolArgs::FunctionArgs(FunctionArgStruct {
+                arguments,
+                super_arguments,
+                return_type,
+                overrides,
+                engine_virtual,
+            }) => {
+                details = format_details(&symbol.tags, &arguments);
+                signature += &format!("({})", join(&arguments, ", "));
+                if let Some(return_type) = return_type {
+                    signature += &format!(" -&gt; {}", escape_html(&return_type));
+                }
+                if let Some(super_arguments) = super_arguments {
+                    properties.push((
+                        "Calls",
+                        format!(
+                            "<code>super.{}({})</code>",
+                            name,
+                            join(&super_arguments, ", ")
+                        ),
+                    ));
+                }
+                if let Some(overrides) = overrides {
+                    let link = overrides
+                        .rfind('.')
+                        .and_then(|pos| context.class_page(&overrides[..pos]))
+                        .map(|page| format!("{}#{}", page, anchor_kind(entry_type)));
+                    let overrides = match link {
+                        Some(link) => format!(
+                            "<a href=\"{}-{}\"><code>{}</code></a>",
+                            escape_html(&link),
+                            name,
+                            escape_html(&overrides)
+                        ),
+                        None => format!("<code>{}</code>", escape_html(&overrides)),
+                    };
+                    properties.push(("Overrides", overrides));
+                }
+                if engine_virtual {
+                    properties.push(("Implements", "engine callback".to_string()));
+                }
+            }
+            SymbolArgs::SignalArgs(arguments) => {
+                details = format_details(&symbol.tags, &arguments);
+                if !arguments.is_empty() {
+                    signature += &format!("({})", join(&arguments, ", "));
+                }
+            }
+            SymbolArgs::VariableArgs(VariableArgStruct {
+                value_type,
+                assignment,
+                annotations,
+                setter,
+                getter,
+            }) => {
+                if let Some(value_type) = value_type {
+                    signature += &format!(": {}", escape_html(&value_type));
+                }
+                if let Some(assignment) = assignment {
+                    signature += &format!(" = {}", escape_html(&assignment));
+                }
+                if !annotations.is_empty() {
+                    properties.push(("Annotations", join(&annotations, ", ")));
+                }
+                if let Some(getter) = getter {
+                    properties.push(("Getter", escape_html(&getter)));
+                }
+                if let Some(setter) = setter {
+                    properties.push(("Setter", escape_html(&setter)));
+                }
+            }
+            SymbolArgs::ExportArgs(ExportArgStruct {
+                value_type,
+                assignment,
+                hint,
+                annotations,
+                setter,
+                getter,
+                ..
+            }) => {
+                if let Some(value_type) = value_type {
+                    signature += &format!(": {}", escape_html(&value_type));
+                }
+                if let Some(assignment) = assignment {
+                    signature += &format!(" = {}", escape_html(&assignment));
+                }
+                if let Some(hint) = hint {
+                    properties.push(("Hint", escape_html(&hint.to_string())));
+                }
+                if !annotations.is_empty() {
+                    properties.push(("Annotations", join(&annotations, ", ")));
+                }
+                if let Some(getter) = getter {
+                    properties.push(("Getter", escape_html(&getter)));
+                }
+                if let Some(setter) = setter {
+                    properties.push(("Setter", escape_html(&setter)));
+                }
+            }
+            SymbolArgs::EnumArgs(values) => {
+                body += "<ul class=\"values\">\n";
+                for val in values {
+                    body += &format!(
+                        "<li id=\"{}constant-{}\"><code>{} = {}</code>{}</li>\n",
+                        escape_html(scope),
+                        escape_html(&val.name),
+                        escape_html(&val.name),
+                        val.value,
+                        context.format_comments(val.text, val.doc_comment)
+                    );
+                }
+                body += "</ul>\n";
+            }
+            SymbolArgs::ClassArgs(ClassArgStruct {
+                extends,
+                entries,
+                inherited,
+                ..
+            }) => {
+                if let Some(extends) = extends {
+                    let extends = match context.class_page(&extends) {
+                        Some(page) => format!(
+                            "<a href=\"{}\">{}</a>",
+                            escape_html(&page),
+                            escape_html(&extends)
+                        ),
+                        None => escape_html(&extends),
+                    };
+                    properties.push(("Extends", extends));
+                }
+
+                writeln!(f, "<div class=\"symbol\" id=\"{}\">", escape_html(&anchor))?;
+                writeln!(
+                    f,
+                    "<h{level}><code>{}</code> <a class=\"anchor\" href=\"#{}\">#</a>{}</h{level}>",
+                    signature,
+                    escape_html(&anchor),
+                    context.format_source(&symbol.span),
+                    level = level
+                )?;
+                write!(f, "{}", format_notices(&symbol.tags))?;
+                write!(f, "{}", format_properties(properties))?;
+                write!(
+                    f,
+                    "{}",
+                    context.format_comments(symbol.text, symbol.doc_comment)
+                )?;
+                write!(f, "{}", details)?;
+
+                let scope = format!("{}{}.", scope, symbol.name);
+                write_entries(context, &scope, level + 1, entries, f)?;
+                write_inherited(context, level + 1, inherited, f)?;
+                return writeln!(f, "</div>");
+            }
+        }
+    }
+
+    writeln!(f, "<div class=\"symbol\" id=\"{}\">", escape_html(&anchor))?;
+    writeln!(
+        f,
+        "<h{level}><code>{}</code> <a class=\"anchor\" href=\"#{}\">#</a>{}</h{level}>",
+        signature,
+        escape_html(&anchor),
+        context.format_source(&symbol.span),
+        level = level
+    )?;
+    write!(f, "{}", format_notices(&symbol.tags))?;
+    write!(f, "{}", format_properties(properties))?;
+    write!(
+        f,
+        "{}",
+        context.format_comments(symbol.text, symbol.doc_comment)
+    )?;
+    write!(f, "{}", details)?;
+    write!(f, "{}", body)?;
+    writeln!(f, "</div>")
+}
+
+fn write_entries(
+    context: &Context,
+    scope: &str,
+    level: usize,
+    entries: Vec<DocumentationEntry>,
+    f: &mut File,
+) -> std::io::Result<()> {
+    let level = level.min(MAX_HEADING);
+    for entry in entries {
+        writeln!(f, "<section>")?;
+        writeln!(f, "<h{}>{}</h{}>", level, entry.entry_type, level)?;
+        let mut section = InspectorSection::default();
+        for symbol in entry.symbols {
+            // Exports are shown in the sections of the inspector, like the category bars
+            if let Some(SymbolArgs::ExportArgs(export)) = &symbol.arg {
+                for (depth, name) in export.section.headings(&section) {
+                    writeln!(
+                        f,
+                        "<div class=\"inspector-section {}\">{}</div>",
+                        ["category", "group", "subgroup"][depth],
+                        escape_html(name)
+                    )?;
+                }
+                section = export.section.clone();
+            }
+            write_symbol(context, scope, level + 1, entry.entry_type, symbol, f)?;
+        }
+        writeln!(f, "</section>")?;
+    }
+
+    Ok(())
+}
+
+impl Backend for HtmlBackend {
+    fn get_extension(&self) -> String {
+        "html".to_string()
+    }
+
+    fn generate_output(
+        &self,
+        data: DocumentationData,
+        page: &Page,
+        pages: &[Page],
+        _output_dir: &Path,
+        f: &mut File,
+    ) -> std::io::Result<()> {
+        let context = Context {
+            root: root_prefix(&page.path),
+            page: Some(page),
+            pages,
+        };
+
+        context.write_header(&data.source_file, f)?;
+        writeln!(f, "<h1>{}</h1>", escape_html(&data.source_file))?;
+
+        let mut properties = Vec::new();
+        if let Some(class_name) = data.class_name {
+            properties.push(("Class name", escape_html(&class_name)));
+        }
+        if let Some(extends) = data.extends {
+            let extends = match context.class_page(&extends) {
+                Some(page) => format!(
+                    "<a href=\"{}\">{}</a>",
+                    escape_html(&page),
+                    escape_html(&extends)
+                ),
+                None => escape_html(&extends),
+            };
+            properties.push(("Extends", extends));
+        }
+        if data.tool {
+            properties.push(("Tool", "runs in the editor".to_string()));
+        }
+        write!(f, "{}", format_properties(properties))?;
+        write!(f, "{}", format_notices(&data.tags))?;
+        write!(
+            f,
+            "{}",
+            context.format_comments(data.text, data.doc_comment)
+        )?;
+
+        write_entries(&context, "", 2, data.entries, f)?;
+        write_inherited(&context, 2, data.inherited, f)?;
+        write_footer(f)
+    }
+
+    fn generate_index(&self, pages: &[Page], output_path: &Path) -> std::io::Result<()> {
+        std::fs::create_dir_all(output_path)?;
+        std::fs::write(output_path.join("style.css"), STYLESHEET)?;
+
+        let context = Context {
+            root: String::new(),
+            page: None,
+            pages,
+        };
+
+        let mut f = File::create(output_path.join("index.html"))?;
+        context.write_header("Index", &mut f)?;
+        writeln!(f, "<h1>Index</h1>")?;
+        writeln!(f, "<table>")?;
+        writeln!(f, "<tr><th>Script</th><th>Class name</th></tr>")?;
+        for page in pages {
+            writeln!(
+                f,
+                "<tr><td><a href=\"{}\">{}</a></td><td>{}</td></tr>",
+                escape_html(&page.link("", "html")),
+                escape_html(&PathBuf::from(&page.path).to_string_lossy()),
+                escape_html(page.class_name.as_deref().unwrap_or(""))
+            )?;
+        }
+        writeln!(f, "</table>")?;
+        write_footer(&mut f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::render;
+
+    fn html(source: &str) -> String {
+        let pages = [
+            Page::new(
+                Path::new("sub/player.gd"),
+                "res://sub/player.gd".to_string(),
+                None,
+            ),
+            Page::new(
+                Path::new("enemy.gd"),
+                "res://enemy.gd".to_string(),
+                Some("Enemy".to_string()),
+            ),
+        ];
+        render(&HtmlBackend::new(), source, &pages)
+    }
+
+    #[test]
+    fn headings_stop_at_h6() {
+        let output = html("class A:\n\tclass B:\n\t\tclass C:\n\t\t\tclass D:\n\t\t\t\tvar x\n");
+        assert!(output.contains("<h6><code>x</code>"));
+        assert!(!output.contains("<h7"));
+    }
+
+    #[test]
+    fn links() {
+        let output = html(
+            "## [url=javascript:alert(1)]click[/url], [url=https://godotengine.org]Godot[/url], \
+             [Enemy], [method move]\nvar x\n",
+        );
+        assert!(output.contains("click, <a href=\"https://godotengine.org\">Godot</a>"));
+        assert!(!output.contains("javascript"));
+        assert!(output.contains("<a href=\"../enemy.gd.html\"><code>Enemy</code></a>"));
+        assert!(output.contains("<a href=\"#method-move\"><code>move()</code></a>"));
+    }
+
+    #[test]
+    fn plain_comments_are_not_markup() {
+        let output = html("# [b]x[/b] & y\nvar x\n");
+        assert!(output.contains("[b]x[/b] &amp; y"));
+    }
+
+    #[test]
+    fn notices_and_parameters() {
+        let source = "# Moves\n\
+                      # @param speed How fast\n\
+                      # @deprecated\n\
+                      # @since 1.2\n\
+                      func move(speed: float, dir = 1):\n\
+                      \tpass\n";
+        let output = html(source);
+        assert!(output.contains("<p class=\"notice deprecated\"><b>Deprecated</b></p>"));
+        assert!(output.contains("<p class=\"notice since\"><b>Since</b>: 1.2</p>"));
+        assert!(output.contains(
+            "<tr><td><code>speed</code></td><td>float</td><td></td><td>How fast</td></tr>"
+        ));
+        assert!(output.contains("<tr><td><code>dir</code></td><td></td><td>1</td><td></td></tr>"));
+    }
+
+    #[test]
+    fn script_description() {
+        let source = "## Spawns [b]enemies[/b]\n\
+                      ## @deprecated Use waves\n\
+                      ## @since 1.2\n\
+                      class_name Spawner\n";
+        let output = html(source);
+        assert!(output.contains(
+            "</dl>\n\
+             <p class=\"notice deprecated\"><b>Deprecated</b>: Use waves</p>\n\
+             <p class=\"notice since\"><b>Since</b>: 1.2</p>\n\
+             <div class=\"description\">Spawns <b>enemies</b></div>\n"
+        ));
+    }
+}
@@ -0,0 +1,130 @@
+use crate::backend::{Backend, Page};
+use crate::parser::DocumentationData;
+
+use serde::{Deserialize, Serialize};
+
+use std::ffi::OsStr;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+// Bumped whenever the structure of the generated documents changes incompatibly
+const SCHEMA_VERSION: u32 = 3;
+
+#[derive(Serialize, Deserialize)]
+struct Document {
+    schema_version: u32,
+    path: PathBuf,
+    #[serde(flatten)]
+    data: DocumentationData,
+}
+
+pub struct JsonBackend {}
+
+impl JsonBackend {
+    pub fn new() -> JsonBackend {
+        JsonBackend {}
+    }
+}
+
+impl Backend for JsonBackend {
+    fn get_extension(&self) -> String {
+        "json".to_string()
+    }
+
+    fn generate_output(
+        &self,
+        data: DocumentationData,
+        page: &Page,
+        _pages: &[Page],
+        _output_dir: &Path,
+        f: &mut File,
+    ) -> std::io::Result<()> {
+        let document = Document {
+            schema_version: SCHEMA_VERSION,
+            path: page.path.clone(),
+            data,
+        };
+
+        serde_json::to_writer_pretty(f, &document)?;
+        Ok(())
+    }
+}
+
+/// Reads the documents generated by the json backend from the given directory, along with the
+/// paths of the scripts they document.
+pub fn read_documents(
+    dir: &Path,
+    documents: &mut Vec<(PathBuf, DocumentationData)>,
+) -> Result<(), String> {
+    for entry in std::fs::read_dir(dir).map_err(|e| e.to_string())? {
+        let path = entry.map_err(|e| e.to_string())?.path();
+
+        if path.is_dir() {
+            read_documents(&path, documents)?;
+        } else if path.extension() == Some(OsStr::new("json")) {
+            let f = File::open(&path)
+                .map_err(|e| format!("Failed to open input file: {}, {}", path.display(), e))?;
+            let document: Document = serde_json::from_reader(f)
+                .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+            if document.schema_version != SCHEMA_VERSION {
+                return Err(format!(
+                    "{} has schema version {}, only version {} is supported",
+                    path.display(),
+                    document.schema_version,
+                    SCHEMA_VERSION
+                ));
+            }
+
+            documents.push((document.path, document.data));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::render;
+    use serde_json::Value;
+
+    #[test]
+    fn documents_are_read_back() {
+        let page = Page::new(
+            Path::new("sub/player.gd"),
+            "res://sub/player.gd".to_string(),
+            None,
+        );
+        let source = "class_name Player\n## The speed\nvar speed := 1\n";
+        let output = render(&JsonBackend::new(), source, &[page]);
+
+        let document: Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(document["schema_version"], SCHEMA_VERSION);
+        assert_eq!(document["path"], "sub/player.gd");
+        assert_eq!(document["class_name"], "Player");
+        let symbol = &document["entries"][0]["symbols"][0];
+        assert_eq!(symbol["name"], "speed");
+        assert_eq!(symbol["text"][0], "The speed");
+        assert_eq!(symbol["doc_comment"], true);
+
+        let dir = std::env::temp_dir().join(format!("godotdoc-json-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("sub")).unwrap();
+        std::fs::write(dir.join("sub/player.gd.json"), &output).unwrap();
+        let mut documents = Vec::new();
+        read_documents(&dir, &mut documents).unwrap();
+        assert_eq!(documents.len(), 1);
+        assert_eq!(documents[0].0, PathBuf::from("sub/player.gd"));
+        assert_eq!(documents[0].1.class_name.as_deref(), Some("Player"));
+
+        // Documents of other layouts are rejected instead of being misread
+        let old = output.replacen(
+            &format!("\"schema_version\": {}", SCHEMA_VERSION),
+            "\"schema_version\": 2",
+            1,
+        );
+        std::fs::write(dir.join("sub/player.gd.json"), old).unwrap();
+        let error = read_documents(&dir, &mut Vec::new()).unwrap_err();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(error.ends_with("has schema version 2, only version 3 is supported"));
+    }
+}
@@ -1,8 +1,12 @@
 use std::fs::File;
 use std::io::Write;
+use std::path::Path;
 
-use crate::backend::Backend;
-use crate::parser::{DocumentationData, DocumentationEntry};
+use crate::backend::{anchor_kind, is_safe_url, notices, parameters, reference_link};
+use crate::backend::{root_prefix, Backend, Page};
+use crate::markup::{parse_markup, Markup, ReferenceKind};
+use crate::parser::{ClassArgStruct, DocumentationData, DocumentationEntry, InheritedMembers};
+use crate::parser::{DocTags, FunctionArgument, InspectorSection, SourceSpan, Symbol};
 use crate::parser::{ExportArgStruct, FunctionArgStruct, SymbolArgs, VariableArgStruct};
 
 use std::fmt::Display;
@@ -35,18 +39,180 @@ fn sanitize_markdown_format(f: &impl Display) -> String {
     sanitize_markdown(format!("{}", f))
 }
 
-fn format_comments(prefix: &String, text: Vec<String>) -> String {
+fn format_code(code: String) -> String {
+    if code.contains('`') {
+        format!("`` {} ``", code)
+    } else {
+        format!("`{}`", code)
+    }
+}
+
+// The pages, which references in doc comments link to
+struct Links<'a> {
+    root: String,
+    pages: &'a [Page],
+}
+
+fn format_reference(links: &Links, kind: ReferenceKind, target: String) -> String {
+    let label = match kind {
+        ReferenceKind::Method => format_code(format!("{}()", target)),
+        _ => format_code(target.clone()),
+    };
+
+    match reference_link(links.pages, &links.root, kind, &target, "md") {
+        Some(link) => format!("[{}]({})", label, link),
+        None => label,
+    }
+}
+
+fn format_markup(prefix: &str, links: &Links, nodes: Vec<Markup>) -> String {
+    let mut s = String::new();
+
+    for node in nodes {
+        match node {
+            Markup::Text(text) => s += &sanitize_markdown(text),
+            Markup::LineBreak => s += &format!("  \n{}    ", prefix),
+            Markup::Bold(children) => {
+                s += &format!("**{}**", format_markup(prefix, links, children))
+            }
+            Markup::Italic(children) => {
+                s += &format!("*{}*", format_markup(prefix, links, children))
+            }
+            Markup::Code(code) => s += &format_code(code),
+            Markup::CodeBlock(code) => {
+                s += &format!(
+                    "\n\n{prefix}    ```gdscript\n{prefix}    {}\n{prefix}    ```\n\n{prefix}    ",
+                    code.replace('\n', &format!("\n{}    ", prefix)),
+                    prefix = prefix
+                )
+            }
+            Markup::Reference(kind, target) => s += &format_reference(links, kind, target),
+            Markup::Link(url, children) if is_safe_url(&url) => {
+                s += &format!("[{}]({})", format_markup(prefix, links, children), url)
+            }
+            Markup::Link(_, children) => s += &format_markup(prefix, links, children),
+        }
+    }
+
+    s
+}
+
+fn format_comments(prefix: &String, links: &Links, text: Vec<String>, doc: bool) -> String {
     if text.is_empty() {
         return "  \n".to_string();
     }
+    if !doc {
+        return format!(
+            "  \n{prefix}    ```\n{prefix}    {}\n{prefix}    ```\n\n",
+            text.join(format!("\n{}    ", prefix).as_str()),
+            prefix = prefix,
+        );
+    }
 
     format!(
-        "  \n{prefix}    ```\n{prefix}    {}\n{prefix}    ```\n\n",
-        text.join(format!("\n{}    ", prefix).as_str()),
-        prefix = prefix,
+        "  \n{}    {}\n\n",
+        prefix,
+        format_markup(prefix, links, parse_markup(&text, true)).trim()
     )
 }
 
+// The description of the whole script, which isn't part of a list item like the ones of symbols
+fn format_description(links: &Links, text: Vec<String>, doc: bool) -> String {
+    let comments = format_comments(&String::new(), links, text, doc);
+    let lines = comments
+        .trim_start()
+        .lines()
+        .map(|line| line.strip_prefix("    ").unwrap_or(line))
+        .collect::<Vec<_>>();
+    format!("{}\n\n", lines.join("\n").trim_end())
+}
+
+fn sanitize_markdown_table(s: String) -> String {
+    sanitize_markdown(s).replace("|", "\\|")
+}
+
+fn format_notices(prefix: &str, tags: &DocTags) -> String {
+    let mut s = String::new();
+
+    for (_, title, text) in notices(tags) {
+        s += &format!("  \n{}**{}**", prefix, title);
+        if !text.is_empty() {
+            s += &format!(": {}", sanitize_markdown(text.to_string()));
+        }
+    }
+
+    s
+}
+
+fn format_source(prefix: &str, page: &Page, span: &SourceSpan) -> String {
+    match page.source_link(span) {
+        Some(link) => format!(
+            "  \n{}**Source**: [{}:{}]({})",
+            prefix,
+            sanitize_markdown(page.path.display().to_string()),
+            span.line,
+            link
+        ),
+        None => String::new(),
+    }
+}
+
+fn format_details(prefix: &str, tags: &DocTags, arguments: &[FunctionArgument]) -> String {
+    let mut details = String::new();
+
+    let parameters = parameters(tags, arguments);
+    if !parameters.is_empty() {
+        details += &format!(
+            "{prefix}    **Parameters**:\n\n{prefix}    | Name | Type | Default | Description |\n{prefix}    | --- | --- | --- | --- |\n",
+            prefix = prefix
+        );
+        for (argument, text) in parameters {
+            details += &format!(
+                "{}    | {} | {} | {} | {} |\n",
+                prefix,
+                sanitize_markdown_table(argument.name.clone()),
+                sanitize_markdown_table(argument.value_type.clone().unwrap_or_default()),
+                sanitize_markdown_table(argument.default_value.clone().unwrap_or_default()),
+                sanitize_markdown_table(text.unwrap_or_default().to_string())
+            );
+        }
+        details += "\n";
+    }
+    if let Some(returns) = &tags.returns {
+        details += &format!(
+            "{}    **Returns**: {}\n\n",
+            prefix,
+            sanitize_markdown(returns.clone())
+        );
+    }
+
+    details
+}
+
+// Headings of the sections of the inspector an export starts, given the section of the export
+// before it
+fn format_sections(indent: &str, previous: &mut InspectorSection, symbol: &Symbol) -> String {
+    let Some(SymbolArgs::ExportArgs(export)) = &symbol.arg else {
+        return String::new();
+    };
+
+    let headings = export
+        .section
+        .headings(previous)
+        .into_iter()
+        .map(|(depth, name)| {
+            format!(
+                "{}{} {}\n\n",
+                indent,
+                "#".repeat(4 + depth),
+                sanitize_markdown(name.to_string())
+            )
+        })
+        .collect();
+    *previous = export.section.clone();
+    headings
+}
+
 fn join<T: Display>(v: Vec<T>, s: &str) -> String {
     v.iter()
         .map(|x| sanitize_markdown_format(x))
@@ -54,24 +220,79 @@ fn join<T: Display>(v: Vec<T>, s: &str) -> String {
         .join(s)
 }
 
+fn write_inherited(
+    prefix: String,
+    inherited: Vec<InheritedMembers>,
+    f: &mut File,
+) -> std::io::Result<()> {
+    for members in inherited {
+        writeln!(
+            f,
+            "{}* **Inherited from {}**:  ",
+            prefix,
+            sanitize_markdown(members.ancestor)
+        )?;
+        for entry in members.entries {
+            writeln!(
+                f,
+                "{}    * {}: {}  ",
+                prefix,
+                entry.entry_type,
+                join(entry.names, ", ")
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
 fn write_symbols(
     prefix: String,
+    scope: &str,
+    page: &Page,
+    links: &Links,
     entries: Vec<DocumentationEntry>,
     f: &mut File,
 ) -> std::io::Result<()> {
     for entry in entries {
-        write!(f, "{}* **{}**:  \n", prefix, entry.entry_type)?;
+        writeln!(f, "{}* **{}**:  ", prefix, entry.entry_type)?;
 
+        let kind = anchor_kind(entry.entry_type);
+        let mut section = InspectorSection::default();
         for entry in entry.symbols {
-            let sanitized_name = sanitize_markdown(entry.name);
+            write!(
+                f,
+                "{}",
+                format_sections(&format!("{}    ", prefix), &mut section, &entry)
+            )?;
+            let anchor = format!("{}{}-{}", scope, kind, entry.name);
+            let sanitized_name = sanitize_markdown(entry.name.clone());
+
+            let notices =
+                format_notices(&prefix, &entry.tags) + &format_source(&prefix, page, &entry.span);
+            let details = match &entry.arg {
+                Some(SymbolArgs::FunctionArgs(function)) => {
+                    format_details(&prefix, &entry.tags, &function.arguments)
+                }
+                Some(SymbolArgs::SignalArgs(arguments)) => {
+                    format_details(&prefix, &entry.tags, arguments)
+                }
+                _ => format_details(&prefix, &entry.tags, &[]),
+            };
 
-            write!(f, "{}    * {}", prefix, sanitized_name)?;
+            write!(
+                f,
+                "{}    * <a id=\"{}\"></a>{}",
+                prefix, anchor, sanitized_name
+            )?;
             if let Some(args) = entry.arg {
                 match args {
                     SymbolArgs::FunctionArgs(FunctionArgStruct {
                         arguments,
                         super_arguments,
                         return_type,
+                        overrides,
+                        engine_virtual,
                     }) => {
                         write!(f, "({})", join(arguments, ", "))?;
                         if let Some(return_type) = return_type {
@@ -86,10 +307,27 @@ fn write_symbols(
                                 join(super_arguments, ", ")
                             )?;
                         }
+                        if let Some(overrides) = overrides {
+                            write!(
+                                f,
+                                "  \n{}**Overrides**: {}",
+                                prefix,
+                                sanitize_markdown(overrides)
+                            )?;
+                        }
+                        if engine_virtual {
+                            write!(f, "  \n{}**Implements**: engine callback", prefix)?;
+                        }
+                    }
+                    SymbolArgs::SignalArgs(arguments) => {
+                        if !arguments.is_empty() {
+                            write!(f, "({})", join(arguments, ", "))?;
+                        }
                     }
                     SymbolArgs::VariableArgs(VariableArgStruct {
                         value_type,
                         assignment,
+                        annotations,
                         setter,
                         getter,
                     }) => {
@@ -99,6 +337,14 @@ fn write_symbols(
                         if let Some(assignment) = assignment {
                             write!(f, " = `{}`", sanitize_markdown_quoted(assignment))?;
                         }
+                        if !annotations.is_empty() {
+                            write!(
+                                f,
+                                "  \n{}**Annotations**: {}",
+                                prefix,
+                                join(annotations, ", ")
+                            )?;
+                        }
                         if let Some(getter) = getter {
                             write!(f, "  \n{}**Getter**: {}", prefix, sanitize_markdown(getter))?;
                         }
@@ -109,25 +355,34 @@ fn write_symbols(
                     SymbolArgs::ExportArgs(ExportArgStruct {
                         value_type,
                         assignment,
-                        options,
+                        hint,
+                        annotations,
                         setter,
                         getter,
+                        ..
                     }) => {
                         if let Some(value_type) = value_type {
-                            if options.len() == 0 {
-                                write!(f, ": {}", sanitize_markdown(value_type))?;
-                            } else {
-                                write!(
-                                    f,
-                                    ": ({}, {})",
-                                    sanitize_markdown(value_type),
-                                    sanitize_markdown(options.join(", "))
-                                )?;
-                            }
+                            write!(f, ": {}", sanitize_markdown(value_type))?;
                         }
                         if let Some(assignment) = assignment {
                             write!(f, " = `{}`", sanitize_markdown_quoted(assignment))?;
                         }
+                        if let Some(hint) = hint {
+                            write!(
+                                f,
+                                "  \n{}**Hint**: {}",
+                                prefix,
+                                sanitize_markdown(hint.to_string())
+                            )?;
+                        }
+                        if !annotations.is_empty() {
+                            write!(
+                                f,
+                                "  \n{}**Annotations**: {}",
+                                prefix,
+                                join(annotations, ", ")
+                            )?;
+                        }
                         if let Some(getter) = getter {
                             write!(f, "  \n{}**Getter**: {}", prefix, sanitize_markdown(getter))?;
                         }
@@ -145,17 +400,53 @@ fn write_symbols(
                                 sanitize_markdown(val.name),
                                 val.value
                             )?;
-                            write!(f, "{}", format_comments(&prefix, val.text))?;
+                            write!(
+                                f,
+                                "{}",
+                                format_comments(&prefix, links, val.text, val.doc_comment)
+                            )?;
                         }
                     }
-                    SymbolArgs::ClassArgs(entries) => {
-                        write!(f, "{}", format_comments(&prefix, entry.text))?;
-                        write_symbols(format!("{}{}", prefix, "        "), entries, f)?;
+                    SymbolArgs::ClassArgs(ClassArgStruct {
+                        extends,
+                        entries,
+                        inherited,
+                        ..
+                    }) => {
+                        if let Some(extends) = extends {
+                            write!(
+                                f,
+                                "  \n{}**Extends**: {}",
+                                prefix,
+                                sanitize_markdown(extends)
+                            )?;
+                        }
+                        write!(f, "{}", notices)?;
+                        write!(
+                            f,
+                            "{}",
+                            format_comments(&prefix, links, entry.text, entry.doc_comment)
+                        )?;
+                        write_symbols(
+                            format!("{}{}", prefix, "        "),
+                            &format!("{}{}.", scope, entry.name),
+                            page,
+                            links,
+                            entries,
+                            f,
+                        )?;
+                        write_inherited(format!("{}{}", prefix, "        "), inherited, f)?;
                         continue;
                     }
                 }
             }
-            write!(f, "{}", format_comments(&prefix, entry.text))?;
+            write!(
+                f,
+                "{}{}{}",
+                notices,
+                format_comments(&prefix, links, entry.text, entry.doc_comment),
+                details
+            )?;
         }
     }
 
@@ -167,22 +458,76 @@ impl Backend for MarkdownBackend {
         "md".to_string()
     }
 
-    fn generate_output(&self, data: DocumentationData, f: &mut File) -> std::io::Result<()> {
+    fn generate_output(
+        &self,
+        data: DocumentationData,
+        page: &Page,
+        pages: &[Page],
+        _output_dir: &Path,
+        f: &mut File,
+    ) -> std::io::Result<()> {
+        let links = Links {
+            root: root_prefix(&page.path),
+            pages,
+        };
         write!(f, "## {}\n\n", sanitize_markdown(data.source_file))?;
 
+        let has_header = data.class_name.is_some() || data.extends.is_some() || data.tool;
+        if let Some(class_name) = data.class_name {
+            writeln!(f, "**Class name**: {}  ", sanitize_markdown(class_name))?;
+        }
+        if let Some(extends) = data.extends {
+            writeln!(f, "**Extends**: {}  ", sanitize_markdown(extends))?;
+        }
+        if data.tool {
+            writeln!(f, "**Tool**: runs in the editor  ")?;
+        }
+        if has_header {
+            writeln!(f)?;
+        }
+        let notices = format_notices("", &data.tags);
+        if !notices.is_empty() {
+            write!(f, "{}\n\n", notices.trim_start())?;
+        }
+        if !data.text.is_empty() {
+            write!(
+                f,
+                "{}",
+                format_description(&links, data.text, data.doc_comment)
+            )?;
+        }
+
         for entry in data.entries {
-            write!(f, "### {}:  \n", entry.entry_type)?;
+            writeln!(f, "### {}:  ", entry.entry_type)?;
 
+            let kind = anchor_kind(entry.entry_type);
+            let mut section = InspectorSection::default();
             for entry in entry.symbols {
-                let sanitized_name = sanitize_markdown(entry.name);
+                write!(f, "{}", format_sections("", &mut section, &entry))?;
+                let anchor = format!("{}-{}", kind, entry.name);
+                let sanitized_name = sanitize_markdown(entry.name.clone());
 
-                write!(f, "* {}", sanitized_name)?;
+                let notices =
+                    format_notices("", &entry.tags) + &format_source("", page, &entry.span);
+                let details = match &entry.arg {
+                    Some(SymbolArgs::FunctionArgs(function)) => {
+                        format_details("", &entry.tags, &function.arguments)
+                    }
+                    Some(SymbolArgs::SignalArgs(arguments)) => {
+                        format_details("", &entry.tags, arguments)
+                    }
+                    _ => format_details("", &entry.tags, &[]),
+                };
+
+                write!(f, "* <a id=\"{}\"></a>{}", anchor, sanitized_name)?;
                 if let Some(args) = entry.arg {
                     match args {
                         SymbolArgs::FunctionArgs(FunctionArgStruct {
                             arguments,
                             super_arguments,
                             return_type,
+                            overrides,
+                            engine_virtual,
                         }) => {
                             write!(f, "({})", join(arguments, ", "))?;
                             if let Some(return_type) = return_type {
@@ -196,10 +541,22 @@ impl Backend for MarkdownBackend {
                                     join(super_arguments, ", ")
                                 )?;
                             }
+                            if let Some(overrides) = overrides {
+                                write!(f, "  \n**Overrides**: {}", sanitize_markdown(overrides))?;
+                            }
+                            if engine_virtual {
+                                write!(f, "  \n**Implements**: engine callback")?;
+                            }
+                        }
+                        SymbolArgs::SignalArgs(arguments) => {
+                            if !arguments.is_empty() {
+                                write!(f, "({})", join(arguments, ", "))?;
+                            }
                         }
                         SymbolArgs::VariableArgs(VariableArgStruct {
                             value_type,
                             assignment,
+                            annotations,
                             setter,
                             getter,
                         }) => {
@@ -209,6 +566,9 @@ impl Backend for MarkdownBackend {
                             if let Some(assignment) = assignment {
                                 write!(f, " = `{}`", sanitize_markdown_quoted(assignment))?;
                             }
+                            if !annotations.is_empty() {
+                                write!(f, "  \n**Annotations**: {}", join(annotations, ", "))?;
+                            }
                             if let Some(getter) = getter {
                                 write!(f, "  \n**Getter**: {}", sanitize_markdown(getter))?;
                             }
@@ -219,25 +579,24 @@ impl Backend for MarkdownBackend {
                         SymbolArgs::ExportArgs(ExportArgStruct {
                             value_type,
                             assignment,
-                            options,
+                            hint,
+                            annotations,
                             setter,
                             getter,
+                            ..
                         }) => {
                             if let Some(value_type) = value_type {
-                                if options.len() == 0 {
-                                    write!(f, ": {}", sanitize_markdown(value_type))?;
-                                } else {
-                                    write!(
-                                        f,
-                                        ": ({}, {})",
-                                        sanitize_markdown(value_type),
-                                        sanitize_markdown(options.join(", "))
-                                    )?;
-                                }
+                                write!(f, ": {}", sanitize_markdown(value_type))?;
                             }
                             if let Some(assignment) = assignment {
                                 write!(f, " = `{}`", sanitize_markdown_quoted(assignment))?;
                             }
+                            if let Some(hint) = hint {
+                                write!(f, "  \n**Hint**: {}", sanitize_markdown(hint.to_string()))?;
+                            }
+                            if !annotations.is_empty() {
+                                write!(f, "  \n**Annotations**: {}", join(annotations, ", "))?;
+                            }
                             if let Some(getter) = getter {
                                 write!(f, "  \n**Getter**: {}", sanitize_markdown(getter))?;
                             }
@@ -258,27 +617,128 @@ impl Backend for MarkdownBackend {
                                     write!(
                                         f,
                                         "  \n    {}",
-                                        format_comments(&"".to_string(), val.text)
+                                        format_comments(
+                                            &"".to_string(),
+                                            &links,
+                                            val.text,
+                                            val.doc_comment
+                                        )
                                     )?;
                                 }
                             }
                         }
-                        SymbolArgs::ClassArgs(entries) => {
+                        SymbolArgs::ClassArgs(ClassArgStruct {
+                            extends,
+                            entries,
+                            inherited,
+                            ..
+                        }) => {
+                            if let Some(extends) = extends {
+                                write!(f, "  \n**Extends**: {}", sanitize_markdown(extends))?;
+                            }
                             write!(
                                 f,
-                                "  \n{}  \n",
-                                format_comments(&"".to_string(), entry.text)
+                                "{}  \n{}  \n",
+                                notices,
+                                format_comments(
+                                    &"".to_string(),
+                                    &links,
+                                    entry.text,
+                                    entry.doc_comment
+                                )
                             )?;
-                            write_symbols("    ".to_string(), entries, f)?;
+                            write_symbols(
+                                "    ".to_string(),
+                                &format!("{}.", entry.name),
+                                page,
+                                &links,
+                                entries,
+                                f,
+                            )?;
+                            write_inherited("    ".to_string(), inherited, f)?;
                             continue;
                         }
                     }
                 }
-                write!(f, "  \n{}", format_comments(&"".to_string(), entry.text))?;
+                write!(
+                    f,
+                    "{}  \n{}{}",
+                    notices,
+                    format_comments(&"".to_string(), &links, entry.text, entry.doc_comment),
+                    details
+                )?;
+            }
+            writeln!(f, "  ")?;
+        }
+
+        for members in data.inherited {
+            writeln!(
+                f,
+                "### Inherited from {}:  ",
+                sanitize_markdown(members.ancestor)
+            )?;
+
+            for entry in members.entries {
+                writeln!(
+                    f,
+                    "* **{}**: {}  ",
+                    entry.entry_type,
+                    join(entry.names, ", ")
+                )?;
             }
-            write!(f, "  \n")?;
+            writeln!(f, "  ")?;
         }
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::render;
+
+    fn comments(text: &[&str], doc: bool) -> String {
+        let links = Links {
+            root: String::new(),
+            pages: &[],
+        };
+        let text = text.iter().map(|s| s.to_string()).collect();
+        format_comments(&"".to_string(), &links, text, doc)
+    }
+
+    #[test]
+    fn plain_comments_are_fenced() {
+        assert_eq!(
+            comments(&["see arr[i]", "for details"], false),
+            "  \n    ```\n    see arr[i]\n    for details\n    ```\n\n"
+        );
+    }
+
+    #[test]
+    fn doc_comments_link_references() {
+        assert_eq!(
+            comments(&["Use [method heal] or [member Inner.hp], not arr[i]"], true),
+            "  \n    Use [`heal()`](#method-heal) or [`Inner.hp`](#Inner.member-hp), not arr\\[i\\]\n\n"
+        );
+    }
+
+    #[test]
+    fn script_description() {
+        let pages = [Page::new(
+            Path::new("spawner.gd"),
+            "res://spawner.gd".to_string(),
+            None,
+        )];
+        let source = "## Spawns [b]enemies[/b]\n\
+                      ## @deprecated Use waves\n\
+                      ## @since 1.2\n\
+                      class_name Spawner\n";
+        let output = render(&MarkdownBackend::new(), source, &pages);
+        assert!(output.contains(
+            "**Class name**: Spawner  \n\n\
+             **Deprecated**: Use waves  \n**Since**: 1.2\n\n\
+             Spawns **enemies**\n\n"
+        ));
+    }
+}
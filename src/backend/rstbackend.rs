@@ -0,0 +1,880 @@
+use crate::backend::{is_safe_url, notices, parameters, Backend, Page};
+use crate::markup::{parse_markup, Markup, ReferenceKind};
+use crate::parser::{ClassArgStruct, DocTags, DocumentationData, DocumentationEntry, EntryType};
+use crate::parser::{ExportArgStruct, FunctionArgStruct, FunctionArgument, InheritedMembers};
+use crate::parser::{InspectorSection, SourceSpan, Symbol, SymbolArgs, VariableArgStruct};
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+// Underline characters of the section levels, the same Godot's class reference uses
+const HEADINGS: [char; 6] = ['=', '-', '~', '^', '"', '\''];
+
+pub struct RstBackend {}
+
+impl RstBackend {
+    pub fn new() -> RstBackend {
+        RstBackend {}
+    }
+}
+
+fn escape_rst(s: &str) -> String {
+    let mut escaped = String::new();
+    let mut chars = s.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' | '*' | '`' | '|' => escaped.push('\\'),
+            // A trailing underscore would turn the word into a reference
+            '_' if !chars.peek().is_some_and(|n| n.is_alphanumeric()) => escaped.push('\\'),
+            _ => (),
+        }
+        escaped.push(c);
+    }
+
+    escaped
+}
+
+fn literal(s: &str) -> String {
+    if s.is_empty() {
+        String::new()
+    } else {
+        format!("``{}``", s)
+    }
+}
+
+// Inline markup has to be separated from preceding words, "\ " is an invisible separator
+fn push_inline(s: &mut String, markup: &str) {
+    if s.ends_with(|c: char| !c.is_whitespace() && !"([{'\"-/:".contains(c)) {
+        *s += "\\ ";
+    }
+    *s += markup;
+}
+
+fn sanitize_label(s: &str) -> String {
+    s.chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+fn page_label(page: &Page) -> String {
+    page.class_name
+        .clone()
+        .unwrap_or_else(|| sanitize_label(&page.path.display().to_string()))
+}
+
+fn docname(page: &Page) -> String {
+    page.path
+        .iter()
+        .map(|c| c.to_string_lossy().to_string())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn heading(s: &mut String, level: usize, title: &str) {
+    let underline = HEADINGS[level.min(HEADINGS.len() - 1)];
+    *s += &format!(
+        "{}\n{}\n\n",
+        title,
+        underline.to_string().repeat(title.chars().count())
+    );
+}
+
+fn target(s: &mut String, label: &str) {
+    *s += &format!(".. _{}:\n\n", label);
+}
+
+fn write_table(s: &mut String, rows: &[Vec<String>]) {
+    let widths = (0..rows[0].len())
+        .map(|i| rows.iter().map(|r| r[i].chars().count()).max().unwrap())
+        .collect::<Vec<_>>();
+    let border = widths
+        .iter()
+        .map(|w| format!("+{}", "-".repeat(w + 2)))
+        .collect::<String>()
+        + "+";
+
+    *s += ".. table::\n   :widths: auto\n\n";
+    *s += &format!("   {}\n", border);
+    for row in rows {
+        *s += "   ";
+        for (cell, width) in row.iter().zip(&widths) {
+            *s += &format!("| {}{} ", cell, " ".repeat(width - cell.chars().count()));
+        }
+        *s += &format!("|\n   {}\n", border);
+    }
+    s.push('\n');
+}
+
+fn plain_text(nodes: Vec<Markup>) -> String {
+    let mut s = String::new();
+    for node in nodes {
+        match node {
+            Markup::Text(text) | Markup::Code(text) | Markup::CodeBlock(text) => s += &text,
+            Markup::LineBreak => s.push(' '),
+            Markup::Bold(children) | Markup::Italic(children) | Markup::Link(_, children) => {
+                s += &plain_text(children)
+            }
+            Markup::Reference(_, target) => s += &target,
+        }
+    }
+    s
+}
+
+fn label_kind(entry_type: EntryType) -> &'static str {
+    match entry_type {
+        EntryType::CLASS => "class",
+        EntryType::SIGNAL => "signal",
+        EntryType::FUNC => "method",
+        EntryType::VAR | EntryType::EXPORT => "property",
+        EntryType::CONST => "constant",
+        EntryType::ENUM => "enum",
+    }
+}
+
+fn symbol_label(class_label: &str, entry_type: EntryType, name: &str) -> String {
+    match entry_type {
+        EntryType::CLASS => format!("class_{}_{}", class_label, name),
+        EntryType::ENUM => format!("enum_{}_{}", class_label, name),
+        _ => format!(
+            "class_{}_{}_{}",
+            class_label,
+            label_kind(entry_type),
+            sanitize_label(name)
+        ),
+    }
+}
+
+fn symbols<'a>(
+    entries: &'a [DocumentationEntry],
+    types: &[EntryType],
+) -> Vec<(EntryType, &'a Symbol)> {
+    entries
+        .iter()
+        .filter(|e| types.contains(&e.entry_type))
+        .flat_map(|e| e.symbols.iter().map(move |s| (e.entry_type, s)))
+        .collect()
+}
+
+struct ClassData {
+    label: String,
+    title: String,
+    header: Vec<String>,
+    text: Vec<String>,
+    doc_comment: bool,
+    tags: DocTags,
+    entries: Vec<DocumentationEntry>,
+    inherited: Vec<InheritedMembers>,
+}
+
+struct Context<'a> {
+    page: &'a Page,
+    pages: &'a [Page],
+    // Labels of the classes declared in the script, by their name relative to it
+    classes: HashMap<String, String>,
+}
+
+impl<'a> Context<'a> {
+    fn class_label(&self, name: &str) -> Option<String> {
+        self.pages
+            .iter()
+            .find(|p| {
+                p.class_name.as_deref() == Some(name) || format!("\"{}\"", p.resource_path) == name
+            })
+            .map(page_label)
+            .or_else(|| self.classes.get(name).cloned())
+    }
+
+    fn format_class(&self, name: &str) -> String {
+        match self.class_label(name) {
+            Some(label) => format!(":ref:`{}<class_{}>`", escape_rst(name), label),
+            None => escape_rst(name),
+        }
+    }
+
+    fn format_type(&self, value_type: &Option<String>) -> String {
+        match value_type.as_deref() {
+            Some("") | None => "Variant".to_string(),
+            Some(value_type) => self.format_class(value_type),
+        }
+    }
+
+    fn format_reference(&self, class_label: &str, kind: ReferenceKind, target: &str) -> String {
+        let entry_type = match kind {
+            ReferenceKind::Class => {
+                return match self.class_label(target) {
+                    Some(label) => format!(":ref:`{}<class_{}>`", escape_rst(target), label),
+                    None => literal(target),
+                }
+            }
+            ReferenceKind::Method => EntryType::FUNC,
+            ReferenceKind::Member => EntryType::VAR,
+            ReferenceKind::Signal => EntryType::SIGNAL,
+            ReferenceKind::Constant => EntryType::CONST,
+            ReferenceKind::Enum => EntryType::ENUM,
+            ReferenceKind::Annotation => return literal(&format!("@{}", target)),
+            ReferenceKind::Param => return literal(target),
+        };
+
+        let (label, name) = match target.rfind('.') {
+            Some(pos) => match self.class_label(&target[..pos]) {
+                Some(label) => (label, &target[pos + 1..]),
+                // Members of engine classes can't be linked to
+                None => return literal(target),
+            },
+            None => (class_label.to_string(), target),
+        };
+        let text = match kind {
+            ReferenceKind::Method => format!("{}()", target),
+            _ => target.to_string(),
+        };
+
+        format!(
+            ":ref:`{}<{}>`",
+            escape_rst(&text),
+            symbol_label(&label, entry_type, name)
+        )
+    }
+
+    fn format_markup(&self, class_label: &str, nodes: Vec<Markup>) -> String {
+        let mut s = String::new();
+        let mut after_inline = false;
+        let mut after_break = false;
+
+        for node in nodes {
+            let inline = match node {
+                Markup::Text(text) => {
+                    // Indentation would start a block quote
+                    let text = if after_break {
+                        text.trim_start()
+                    } else {
+                        &text
+                    };
+                    if after_inline && text.starts_with(|c: char| c.is_alphanumeric()) {
+                        s += "\\ ";
+                    }
+                    s += &escape_rst(text);
+                    false
+                }
+                Markup::LineBreak => {
+                    s.push('\n');
+                    false
+                }
+                Markup::Bold(children) => {
+                    let text = plain_text(children);
+                    if !text.is_empty() {
+                        push_inline(&mut s, &format!("**{}**", escape_rst(&text)));
+                    }
+                    true
+                }
+                Markup::Italic(children) => {
+                    let text = plain_text(children);
+                    if !text.is_empty() {
+                        push_inline(&mut s, &format!("*{}*", escape_rst(&text)));
+                    }
+                    true
+                }
+                Markup::Code(code) => {
+                    push_inline(&mut s, &literal(&code));
+                    true
+                }
+                Markup::CodeBlock(code) => {
+                    s += "\n\n::\n\n";
+                    for line in code.lines() {
+                        s += &format!("    {}\n", line);
+                    }
+                    s.push('\n');
+                    false
+                }
+                Markup::Reference(kind, target) => {
+                    let reference = self.format_reference(class_label, kind, &target);
+                    push_inline(&mut s, &reference);
+                    true
+                }
+                Markup::Link(url, children) if is_safe_url(&url) => {
+                    let text = plain_text(children);
+                    push_inline(&mut s, &format!("`{} <{}>`__", escape_rst(&text), url));
+                    true
+                }
+                Markup::Link(_, children) => {
+                    push_inline(&mut s, &escape_rst(&plain_text(children)));
+                    true
+                }
+            };
+
+            after_inline = inline;
+            after_break = s.is_empty() || s.ends_with('\n');
+        }
+
+        s
+    }
+
+    fn write_description(&self, s: &mut String, class_label: &str, text: &[String], doc: bool) {
+        if !text.is_empty() {
+            *s += self
+                .format_markup(class_label, parse_markup(text, doc))
+                .trim_end();
+            *s += "\n\n";
+        }
+    }
+
+    fn format_source(&self, span: &SourceSpan) -> Option<String> {
+        let link = self.page.source_link(span)?;
+        Some(format!(
+            "**Source:** `{}:{} <{}>`__",
+            escape_rst(&self.page.path.display().to_string()),
+            span.line,
+            link
+        ))
+    }
+
+    fn write_source(&self, s: &mut String, span: &SourceSpan) {
+        if let Some(source) = self.format_source(span) {
+            *s += &format!("{}\n\n", source);
+        }
+    }
+
+    fn write_notices(&self, s: &mut String, tags: &DocTags) {
+        for (class, title, text) in notices(tags) {
+            match class {
+                // Sphinx has a directive for versions, a bare @since has nothing to show
+                "since" if text.is_empty() => (),
+                "since" => *s += &format!(".. versionadded:: {}\n\n", text),
+                _ if text.is_empty() => *s += &format!("**{}**\n\n", title),
+                _ => *s += &format!("**{}:** {}\n\n", title, escape_rst(text)),
+            }
+        }
+    }
+
+    fn write_details(&self, s: &mut String, tags: &DocTags, arguments: &[FunctionArgument]) {
+        let parameters = parameters(tags, arguments);
+        if !parameters.is_empty() {
+            *s += "**Parameters:**\n\n";
+            for (argument, text) in parameters {
+                *s += &format!("- **{}**", escape_rst(&argument.name));
+                if argument.value_type.is_some() {
+                    *s += &format!(" ({})", self.format_type(&argument.value_type));
+                }
+                if let Some(text) = text {
+                    *s += &format!(": {}", escape_rst(text));
+                }
+                s.push('\n');
+            }
+            s.push('\n');
+        }
+        if let Some(returns) = &tags.returns {
+            *s += &format!("**Returns:** {}\n\n", escape_rst(returns));
+        }
+    }
+
+    fn format_arguments(&self, arguments: &[FunctionArgument]) -> String {
+        arguments
+            .iter()
+            .map(|a| {
+                let mut argument = escape_rst(&a.name);
+                if a.value_type.is_some() {
+                    argument += &format!(": {}", self.format_type(&a.value_type));
+                }
+                if let Some(default_value) = &a.default_value {
+                    argument += &format!(" = {}", escape_rst(default_value));
+                }
+                argument
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    fn write_inherited(&self, s: &mut String, level: usize, inherited: &[InheritedMembers]) {
+        if inherited.is_empty() {
+            return;
+        }
+
+        heading(s, level, "Inherited Members");
+        for members in inherited {
+            *s += &format!("From {}:\n\n", self.format_class(&members.ancestor));
+            let label = self.class_label(&members.ancestor);
+            for entry in &members.entries {
+                let names = entry
+                    .names
+                    .iter()
+                    .map(|name| match &label {
+                        Some(label) => format!(
+                            ":ref:`{}<{}>`",
+                            escape_rst(name),
+                            symbol_label(label, entry.entry_type, name)
+                        ),
+                        None => literal(name),
+                    })
+                    .collect::<Vec<_>>();
+                *s += &format!("- {}: {}\n", entry.entry_type, names.join(", "));
+            }
+            s.push('\n');
+        }
+    }
+
+    fn write_class(&self, s: &mut String, level: usize, class: ClassData) {
+        let label = class.label;
+        target(s, &format!("class_{}", label));
+        heading(s, level, &escape_rst(&class.title));
+        for line in class.header {
+            *s += &format!("{}\n\n", line);
+        }
+        self.write_notices(s, &class.tags);
+
+        if !class.text.is_empty() {
+            heading(s, level + 1, "Description");
+            self.write_description(s, &label, &class.text, class.doc_comment);
+            self.write_details(s, &class.tags, &[]);
+        }
+
+        let properties = symbols(&class.entries, &[EntryType::VAR, EntryType::EXPORT]);
+        if !properties.is_empty() {
+            heading(s, level + 1, "Properties");
+            let rows = properties
+                .iter()
+                .map(|(entry_type, symbol)| {
+                    let (value_type, assignment) = match &symbol.arg {
+                        Some(SymbolArgs::VariableArgs(VariableArgStruct {
+                            value_type,
+                            assignment,
+                            ..
+                        }))
+                        | Some(SymbolArgs::ExportArgs(ExportArgStruct {
+                            value_type,
+                            assignment,
+                            ..
+                        })) => (value_type.clone(), assignment.clone()),
+                        _ => (None, None),
+                    };
+                    vec![
+                        self.format_type(&value_type),
+                        format!(
+                            ":ref:`{}<{}>`",
+                            escape_rst(&symbol.name),
+                            symbol_label(&label, *entry_type, &symbol.name)
+                        ),
+                        literal(assignment.as_deref().unwrap_or("")),
+                    ]
+                })
+                .collect::<Vec<_>>();
+            write_table(s, &rows);
+        }
+
+        let methods = symbols(&class.entries, &[EntryType::FUNC]);
+        if !methods.is_empty() {
+            heading(s, level + 1, "Methods");
+            let rows = methods
+                .iter()
+                .map(|(entry_type, symbol)| {
+                    let (arguments, return_type) = match &symbol.arg {
+                        Some(SymbolArgs::FunctionArgs(FunctionArgStruct {
+                            arguments,
+                            return_type,
+                            ..
+                        })) => (self.format_arguments(arguments), return_type.clone()),
+                        _ => (String::new(), None),
+                    };
+                    vec![
+                        self.format_type(&return_type),
+                        format!(
+                            ":ref:`{}<{}>`\\ ({})",
+                            escape_rst(&symbol.name),
+                            symbol_label(&label, *entry_type, &symbol.name),
+                            arguments
+                        ),
+                    ]
+                })
+                .collect::<Vec<_>>();
+            write_table(s, &rows);
+        }
+
+        let signals = symbols(&class.entries, &[EntryType::SIGNAL]);
+        if !signals.is_empty() {
+            heading(s, level + 1, "Signals");
+            for (entry_type, symbol) in signals {
+                target(s, &symbol_label(&label, entry_type, &symbol.name));
+                let arguments = match &symbol.arg {
+                    Some(SymbolArgs::SignalArgs(arguments)) => arguments.as_slice(),
+                    _ => &[],
+                };
+                if arguments.is_empty() {
+                    *s += &format!("**{}**\n\n", escape_rst(&symbol.name));
+                } else {
+                    *s += &format!(
+                        "**{}**\\ ({})\n\n",
+                        escape_rst(&symbol.name),
+                        self.format_arguments(arguments)
+                    );
+                }
+                self.write_source(s, &symbol.span);
+                self.write_notices(s, &symbol.tags);
+                self.write_description(s, &label, &symbol.text, symbol.doc_comment);
+                self.write_details(s, &symbol.tags, arguments);
+            }
+        }
+
+        let enums = symbols(&class.entries, &[EntryType::ENUM]);
+        if !enums.is_empty() {
+            heading(s, level + 1, "Enumerations");
+            for (entry_type, symbol) in enums {
+                let enum_label = symbol_label(&label, entry_type, &symbol.name);
+                target(s, &enum_label);
+                *s += &format!("enum **{}**:\n\n", escape_rst(&symbol.name));
+                self.write_source(s, &symbol.span);
+                self.write_notices(s, &symbol.tags);
+                self.write_description(s, &label, &symbol.text, symbol.doc_comment);
+
+                if let Some(SymbolArgs::EnumArgs(values)) = &symbol.arg {
+                    for value in values {
+                        target(s, &symbol_label(&label, EntryType::CONST, &value.name));
+                        *s += &format!(
+                            ":ref:`{}<{}>` **{}** = ``{}``\n\n",
+                            escape_rst(&symbol.name),
+                            enum_label,
+                            escape_rst(&value.name),
+                            value.value
+                        );
+                        self.write_description(s, &label, &value.text, value.doc_comment);
+                    }
+                }
+            }
+        }
+
+        let constants = symbols(&class.entries, &[EntryType::CONST]);
+        if !constants.is_empty() {
+            heading(s, level + 1, "Constants");
+            for (entry_type, symbol) in constants {
+                target(s, &symbol_label(&label, entry_type, &symbol.name));
+                *s += &format!("**{}**", escape_rst(&symbol.name));
+                if let Some(SymbolArgs::VariableArgs(VariableArgStruct {
+                    value_type,
+                    assignment,
+                    ..
+                })) = &symbol.arg
+                {
+                    if value_type.is_some() {
+                        *s += &format!(": {}", self.format_type(value_type));
+                    }
+                    if let Some(assignment) = assignment {
+                        *s += &format!(" = {}", literal(assignment));
+                    }
+                }
+                *s += "\n\n";
+                self.write_source(s, &symbol.span);
+                self.write_notices(s, &symbol.tags);
+                self.write_description(s, &label, &symbol.text, symbol.doc_comment);
+            }
+        }
+
+        if !properties.is_empty() {
+            heading(s, level + 1, "Property Descriptions");
+            let mut section = InspectorSection::default();
+            for (entry_type, symbol) in properties {
+                // Exports are preceded by the section of the inspector they start
+                if let Some(SymbolArgs::ExportArgs(export)) = &symbol.arg {
+                    if !export.section.headings(&section).is_empty() {
+                        let path = export
+                            .section
+                            .levels()
+                            .into_iter()
+                            .map(|(_, name)| name)
+                            .collect::<Vec<_>>();
+                        *s += &format!(".. rubric:: {}\n\n", escape_rst(&path.join(" / ")));
+                    }
+                    section = export.section.clone();
+                }
+                target(s, &symbol_label(&label, entry_type, &symbol.name));
+                let (value_type, assignment, annotations, setter, getter) = match &symbol.arg {
+                    Some(SymbolArgs::VariableArgs(VariableArgStruct {
+                        value_type,
+                        assignment,
+                        annotations,
+                        setter,
+                        getter,
+                    }))
+                    | Some(SymbolArgs::ExportArgs(ExportArgStruct {
+                        value_type,
+                        assignment,
+                        annotations,
+                        setter,
+                        getter,
+                        ..
+                    })) => (value_type, assignment, &annotations[..], setter, getter),
+                    _ => (&None, &None, &[][..], &None, &None),
+                };
+
+                *s += &format!(
+                    "{} **{}**",
+                    self.format_type(value_type),
+                    escape_rst(&symbol.name)
+                );
+                if let Some(assignment) = assignment {
+                    *s += &format!(" = {}", literal(assignment));
+                }
+                *s += "\n\n";
+
+                if let Some(SymbolArgs::ExportArgs(ExportArgStruct {
+                    hint: Some(hint), ..
+                })) = &symbol.arg
+                {
+                    *s += &format!("**Hint:** {}\n\n", escape_rst(&hint.to_string()));
+                }
+                if !annotations.is_empty() {
+                    let annotations = annotations
+                        .iter()
+                        .map(|a| literal(&a.to_string()))
+                        .collect::<Vec<_>>();
+                    *s += &format!("**Annotations:** {}\n\n", annotations.join(", "));
+                }
+                if let Some(setter) = setter {
+                    *s += &format!("**Setter:** {}\n\n", literal(setter));
+                }
+                if let Some(getter) = getter {
+                    *s += &format!("**Getter:** {}\n\n", literal(getter));
+                }
+                self.write_source(s, &symbol.span);
+                self.write_notices(s, &symbol.tags);
+                self.write_description(s, &label, &symbol.text, symbol.doc_comment);
+            }
+        }
+
+        if !methods.is_empty() {
+            heading(s, level + 1, "Method Descriptions");
+            for (entry_type, symbol) in methods {
+                target(s, &symbol_label(&label, entry_type, &symbol.name));
+                match &symbol.arg {
+                    Some(SymbolArgs::FunctionArgs(FunctionArgStruct {
+                        arguments,
+                        super_arguments,
+                        return_type,
+                        overrides,
+                        engine_virtual,
+                    })) => {
+                        *s += &format!(
+                            "{} **{}**\\ ({})\n\n",
+                            self.format_type(return_type),
+                            escape_rst(&symbol.name),
+                            self.format_arguments(arguments)
+                        );
+                        if let Some(super_arguments) = super_arguments {
+                            *s += &format!(
+                                "**Calls:** super.{}({})\n\n",
+                                escape_rst(&symbol.name),
+                                self.format_arguments(super_arguments)
+                            );
+                        }
+                        if let Some(overrides) = overrides {
+                            *s += &format!(
+                                "**Overrides:** {}\n\n",
+                                self.format_reference(&label, ReferenceKind::Method, overrides)
+                            );
+                        }
+                        if *engine_virtual {
+                            *s += "**Implements:** engine callback\n\n";
+                        }
+                        self.write_source(s, &symbol.span);
+                        self.write_notices(s, &symbol.tags);
+                        self.write_description(s, &label, &symbol.text, symbol.doc_comment);
+                        self.write_details(s, &symbol.tags, arguments);
+                    }
+                    _ => {
+                        *s += &format!("**{}**\n\n", escape_rst(&symbol.name));
+                        self.write_source(s, &symbol.span);
+                        self.write_description(s, &label, &symbol.text, symbol.doc_comment);
+                    }
+                }
+            }
+        }
+
+        self.write_inherited(s, level + 1, &class.inherited);
+
+        for entry in class.entries {
+            if entry.entry_type != EntryType::CLASS {
+                continue;
+            }
+
+            for symbol in entry.symbols {
+                if let Some(SymbolArgs::ClassArgs(ClassArgStruct {
+                    extends,
+                    entries,
+                    inherited,
+                    ..
+                })) = symbol.arg
+                {
+                    let mut header = Vec::new();
+                    if let Some(extends) = extends {
+                        header.push(format!("**Inherits:** {}", self.format_class(&extends)));
+                    }
+                    header.extend(self.format_source(&symbol.span));
+                    let inner = ClassData {
+                        label: format!("{}_{}", label, symbol.name),
+                        title: symbol.name,
+                        header,
+                        text: symbol.text,
+                        doc_comment: symbol.doc_comment,
+                        tags: symbol.tags,
+                        entries,
+                        inherited,
+                    };
+                    self.write_class(s, level + 1, inner);
+                }
+            }
+        }
+    }
+}
+
+// Collects the labels of all inner classes, by their name relative to the script
+fn collect_classes(
+    prefix: &str,
+    label: &str,
+    entries: &[DocumentationEntry],
+    classes: &mut HashMap<String, String>,
+) {
+    for (_, symbol) in symbols(entries, &[EntryType::CLASS]) {
+        if let Some(SymbolArgs::ClassArgs(class)) = &symbol.arg {
+            let name = format!("{}{}", prefix, symbol.name);
+            let inner_label = format!("{}_{}", label, symbol.name);
+            collect_classes(&format!("{}.", name), &inner_label, &class.entries, classes);
+            classes.insert(name, inner_label);
+        }
+    }
+}
+
+impl Backend for RstBackend {
+    fn get_extension(&self) -> String {
+        "rst".to_string()
+    }
+
+    fn generate_output(
+        &self,
+        data: DocumentationData,
+        page: &Page,
+        pages: &[Page],
+        _output_dir: &Path,
+        f: &mut File,
+    ) -> std::io::Result<()> {
+        let label = page_label(page);
+        let mut classes = HashMap::new();
+        collect_classes("", &label, &data.entries, &mut classes);
+        let context = Context {
+            page,
+            pages,
+            classes,
+        };
+
+        let mut header = Vec::new();
+        if let Some(extends) = &data.extends {
+            header.push(format!("**Inherits:** {}", context.format_class(extends)));
+        }
+        if data.class_name.is_some() {
+            header.push(format!("**Script:** {}", literal(&page.resource_path)));
+        }
+        if data.tool {
+            header.push("**Tool:** runs in the editor".to_string());
+        }
+
+        let class = ClassData {
+            label,
+            title: data
+                .class_name
+                .unwrap_or_else(|| page.path.display().to_string()),
+            header,
+            text: data.text,
+            doc_comment: data.doc_comment,
+            tags: data.tags,
+            entries: data.entries,
+            inherited: data.inherited,
+        };
+
+        let mut s = String::new();
+        context.write_class(&mut s, 0, class);
+        write!(f, "{}", s.trim_end())?;
+        writeln!(f)
+    }
+
+    fn generate_index(&self, pages: &[Page], output_path: &Path) -> std::io::Result<()> {
+        let mut f = File::create(output_path.join("index.rst"))?;
+
+        writeln!(f, "API Reference")?;
+        writeln!(f, "=============")?;
+        writeln!(f)?;
+        writeln!(f, ".. toctree::")?;
+        writeln!(f, "   :maxdepth: 1")?;
+        writeln!(f)?;
+
+        let mut docnames = pages.iter().map(docname).collect::<Vec<_>>();
+        docnames.sort();
+        for docname in docnames {
+            writeln!(f, "   {}", docname)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::render;
+
+    fn rst(source: &str) -> String {
+        let pages = [Page::new(
+            Path::new("player.gd"),
+            "res://player.gd".to_string(),
+            Some("Player".to_string()),
+        )];
+        render(&RstBackend::new(), source, &pages)
+    }
+
+    #[test]
+    fn labels() {
+        let output =
+            rst("class_name Player\nfunc move():\n\tpass\nvar health\nenum State {IDLE}\n");
+        assert!(output.starts_with(".. _class_Player:\n\nPlayer\n======\n"));
+        assert!(output.contains(":ref:`move<class_Player_method_move>`"));
+        assert!(output.contains(".. _class_Player_property_health:"));
+        assert!(output.contains(".. _enum_Player_State:"));
+        assert!(output.contains(".. _class_Player_constant_IDLE:"));
+    }
+
+    #[test]
+    fn descriptions() {
+        let source = "## Moves [b]fast[/b], see [url=javascript:x]this[/url] and \
+                      [url=https://a.b]that[/url]\n\
+                      ## @param speed How fast\n\
+                      ## @deprecated\n\
+                      ## @since 1.2\n\
+                      func move(speed: float, dir = 1):\n\
+                      \tpass\n";
+        let output = rst(source);
+        assert!(output.contains("**Deprecated**\n\n.. versionadded:: 1.2\n\n"));
+        assert!(output.contains("Moves **fast**, see this and `that <https://a.b>`__\n"));
+        assert!(output.contains("- **speed** (float): How fast\n- **dir**\n"));
+    }
+
+    #[test]
+    fn script_description() {
+        let source = "## Spawns [b]enemies[/b]\n\
+                      ## @deprecated Use waves\n\
+                      ## @since 1.2\n\
+                      class_name Spawner\n";
+        let output = rst(source);
+        assert!(output.contains(
+            "**Deprecated:** Use waves\n\n\
+             .. versionadded:: 1.2\n\n\
+             Description\n-----------\n\n\
+             Spawns **enemies**"
+        ));
+    }
+
+    #[test]
+    fn escaping() {
+        assert_eq!(escape_rst("a*b`c|d\\e"), "a\\*b\\`c\\|d\\\\e");
+        assert_eq!(
+            escape_rst("_private snake_case trailing_"),
+            "_private snake_case trailing\\_"
+        );
+    }
+}
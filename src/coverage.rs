@@ -0,0 +1,262 @@
+use crate::diagnostic::Diagnostic;
+use crate::parser::{DocumentationEntry, EntryType, Symbol, SymbolArgs};
+use crate::project::ProjectFile;
+
+use std::fmt::{Display, Formatter};
+
+// Order of the symbol kinds in the report
+const KINDS: &[EntryType] = &[
+    EntryType::CLASS,
+    EntryType::ENUM,
+    EntryType::SIGNAL,
+    EntryType::EXPORT,
+    EntryType::CONST,
+    EntryType::FUNC,
+    EntryType::VAR,
+];
+
+#[derive(Clone, Copy, Default)]
+pub struct Count {
+    pub documented: u32,
+    pub total: u32,
+}
+
+impl Count {
+    fn add(&mut self, documented: bool) {
+        self.total += 1;
+        if documented {
+            self.documented += 1;
+        }
+    }
+
+    pub fn percentage(&self) -> f64 {
+        if self.total == 0 {
+            return 100.0;
+        }
+        f64::from(self.documented) * 100.0 / f64::from(self.total)
+    }
+}
+
+/// How many of the listed (public, non-hidden) symbols have a doc comment. Symbols hidden
+/// from the documentation are not counted.
+pub struct Coverage {
+    // Scripts by their path, including the symbols of their inner classes
+    pub files: Vec<(String, Count)>,
+    // Inner classes, named like "sub/player.gd.Inner"
+    pub classes: Vec<(String, Count)>,
+    pub kinds: Vec<(EntryType, Count)>,
+    pub total: Count,
+}
+
+pub fn is_documented(symbol: &Symbol) -> bool {
+    symbol.text.iter().any(|line| !line.trim().is_empty()) || !symbol.tags.is_empty()
+}
+
+/// Counts how many of the listed (public, non-hidden) symbols of all scripts have a doc comment,
+/// the ones without are added to the diagnostics as warnings.
+pub fn measure(files: &[ProjectFile], diagnostics: &mut Vec<Diagnostic>) -> Coverage {
+    let mut coverage = Coverage {
+        files: Vec::new(),
+        classes: Vec::new(),
+        kinds: KINDS.iter().map(|&kind| (kind, Count::default())).collect(),
+        total: Count::default(),
+    };
+
+    for file in files {
+        let path = file.path.strip_prefix(".").unwrap_or(&file.path);
+        let path = path.display().to_string();
+        let mut undocumented = Vec::new();
+        coverage.files.push((path.clone(), Count::default()));
+        count_entries(
+            &path,
+            None,
+            &file.data.entries,
+            &mut coverage,
+            &mut undocumented,
+        );
+
+        // The entries are grouped by kind, the warnings follow the order of the script
+        undocumented.sort_by_key(|d| (d.span.line, d.span.column));
+        diagnostics.extend(undocumented);
+    }
+
+    coverage
+}
+
+// Counts the symbols of the last file, and of the inner class with the given index if they are
+// declared in one
+fn count_entries(
+    filename: &str,
+    class: Option<usize>,
+    entries: &[DocumentationEntry],
+    coverage: &mut Coverage,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    for entry in entries {
+        for symbol in &entry.symbols {
+            let documented = is_documented(symbol);
+            coverage.files.last_mut().unwrap().1.add(documented);
+            if let Some(class) = class {
+                coverage.classes[class].1.add(documented);
+            }
+            coverage.total.add(documented);
+            if let Some((_, count)) = coverage
+                .kinds
+                .iter_mut()
+                .find(|(kind, _)| *kind == entry.entry_type)
+            {
+                count.add(documented);
+            }
+
+            if !documented {
+                diagnostics.push(Diagnostic::warning(
+                    filename,
+                    symbol.span,
+                    format!(
+                        "{} '{}' is not documented",
+                        entry.entry_type.singular(),
+                        symbol.name
+                    ),
+                ));
+            }
+
+            // Inner classes are listed after the class declaring them
+            if let Some(SymbolArgs::ClassArgs(inner)) = &symbol.arg {
+                let name = match class {
+                    Some(class) => &coverage.classes[class].0,
+                    None => filename,
+                };
+                let name = format!("{}.{}", name, symbol.name);
+                coverage.classes.push((name, Count::default()));
+                count_entries(
+                    filename,
+                    Some(coverage.classes.len() - 1),
+                    &inner.entries,
+                    coverage,
+                    diagnostics,
+                );
+            }
+        }
+    }
+}
+
+impl Display for Coverage {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        // At least as wide as the titles and the names of the kinds
+        let width = self
+            .files
+            .iter()
+            .chain(&self.classes)
+            .map(|(name, _)| name.chars().count())
+            .chain([11])
+            .max()
+            .unwrap();
+        let header = |f: &mut Formatter, title: &str| {
+            writeln!(
+                f,
+                "{:width$}  {:>10}  {:>5}  {:>8}",
+                title,
+                "Documented",
+                "Total",
+                "Coverage",
+                width = width
+            )
+        };
+        let row = |f: &mut Formatter, name: &str, count: &Count| {
+            writeln!(
+                f,
+                "{:width$}  {:>10}  {:>5}  {:>7.1}%",
+                name,
+                count.documented,
+                count.total,
+                count.percentage(),
+                width = width
+            )
+        };
+
+        header(f, "File")?;
+        for (name, count) in &self.files {
+            row(f, name, count)?;
+        }
+
+        if !self.classes.is_empty() {
+            writeln!(f)?;
+            header(f, "Inner class")?;
+            for (name, count) in &self.classes {
+                row(f, name, count)?;
+            }
+        }
+
+        writeln!(f)?;
+        header(f, "Kind")?;
+        for (kind, count) in &self.kinds {
+            if count.total > 0 {
+                row(f, &kind.to_string(), count)?;
+            }
+        }
+        row(f, "Total", &self.total)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::project_files;
+
+    fn measure_scripts(scripts: &[(&str, &str)]) -> (Coverage, Vec<Diagnostic>) {
+        let files = project_files(scripts);
+        let mut diagnostics = Vec::new();
+        let coverage = measure(&files, &mut diagnostics);
+        (coverage, diagnostics)
+    }
+
+    fn counts(rows: &[(String, Count)]) -> Vec<(&str, u32, u32)> {
+        rows.iter()
+            .map(|(name, count)| (name.as_str(), count.documented, count.total))
+            .collect()
+    }
+
+    #[test]
+    fn rows_per_file() {
+        let (coverage, diagnostics) = measure_scripts(&[
+            (
+                "player.gd",
+                "## Speed\nvar speed\nvar jump\n\
+                 ## Inner\nclass Inner:\n\t## x\n\tvar x\n\tclass Deep:\n\t\tvar y\n",
+            ),
+            ("sub/enemy.gd", "func attack():\n\tpass\n"),
+        ]);
+
+        assert_eq!(
+            counts(&coverage.files),
+            [("player.gd", 3, 6), ("sub/enemy.gd", 0, 1)]
+        );
+        assert_eq!(
+            counts(&coverage.classes),
+            [("player.gd.Inner", 1, 2), ("player.gd.Inner.Deep", 0, 1)]
+        );
+        assert_eq!((coverage.total.documented, coverage.total.total), (3, 7));
+
+        let undocumented = diagnostics
+            .iter()
+            .map(|d| (d.file.as_str(), d.message.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            undocumented,
+            [
+                ("player.gd", "variable 'jump' is not documented"),
+                ("player.gd", "class 'Deep' is not documented"),
+                ("player.gd", "variable 'y' is not documented"),
+                ("sub/enemy.gd", "function 'attack' is not documented"),
+            ]
+        );
+    }
+
+    #[test]
+    fn empty_scripts_are_covered() {
+        let (coverage, _) = measure_scripts(&[("empty.gd", "extends Node\n")]);
+        assert_eq!(coverage.files[0].1.percentage(), 100.0);
+        assert!(coverage.classes.is_empty());
+        assert!(!coverage.to_string().contains("Inner class"));
+    }
+}
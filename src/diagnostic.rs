@@ -0,0 +1,400 @@
+use crate::parser::SourceSpan;
+
+use ansi_term::Colour::{Blue, Red, Yellow};
+use ansi_term::Style;
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use std::fmt::{Display, Formatter};
+
+// Width tabs are expanded to in source excerpts
+const TAB_WIDTH: usize = 4;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+// A secondary location, which helps to understand the diagnostic
+#[derive(Clone, Debug, Serialize)]
+pub struct Note {
+    pub span: SourceSpan,
+    pub message: String,
+}
+
+/// A problem found while parsing a script, located by the path of the script and the span inside of it.
+/// Errors which don't concern a script have neither.
+#[derive(Clone, Debug, Serialize)]
+pub struct Diagnostic {
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub file: String,
+    #[serde(skip_serializing_if = "is_unknown")]
+    pub span: SourceSpan,
+    pub severity: Severity,
+    // Name of the lint rule, which found the problem
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rule: Option<String>,
+    pub message: String,
+    pub notes: Vec<Note>,
+}
+
+impl Diagnostic {
+    pub fn error(file: &str, span: SourceSpan, message: String) -> Diagnostic {
+        Diagnostic {
+            file: file.to_string(),
+            span,
+            severity: Severity::Error,
+            rule: None,
+            message,
+            notes: Vec::new(),
+        }
+    }
+
+    pub fn warning(file: &str, span: SourceSpan, message: String) -> Diagnostic {
+        Diagnostic {
+            file: file.to_string(),
+            span,
+            severity: Severity::Warning,
+            rule: None,
+            message,
+            notes: Vec::new(),
+        }
+    }
+
+    pub fn with_rule(mut self, rule: &str) -> Diagnostic {
+        self.rule = Some(rule.to_string());
+        self
+    }
+
+    pub fn with_note(mut self, span: SourceSpan, message: &str) -> Diagnostic {
+        self.notes.push(Note {
+            span,
+            message: message.to_string(),
+        });
+        self
+    }
+
+    /// Renders the diagnostic like a compiler would, with the lines of the source it refers to
+    /// and the spans underlined. Without the source only the location is printed.
+    pub fn render(&self, source: Option<&str>, colors: bool) -> String {
+        let style = |style: Style| if colors { style } else { Style::new() };
+        let severity = style(match self.severity {
+            Severity::Error => Red.bold(),
+            Severity::Warning => Yellow.bold(),
+        });
+        let gutter = style(Blue.bold());
+
+        // The primary span is marked with '^', notes with '-'
+        let mut labels = vec![(self.span, "", '^', severity)];
+        for note in &self.notes {
+            labels.push((note.span, &note.message, '-', gutter));
+        }
+        let lines = source
+            .map(|s| s.lines().collect::<Vec<_>>())
+            .unwrap_or_default();
+        let mut line_numbers = labels
+            .iter()
+            .map(|(span, ..)| span.line)
+            .filter(|&l| l > 0 && l as usize <= lines.len())
+            .collect::<Vec<_>>();
+        line_numbers.sort_unstable();
+        line_numbers.dedup();
+
+        let width = line_numbers.last().map_or(0, |l| l.to_string().len());
+        let blank = " ".repeat(width);
+
+        let mut s = format!(
+            "{}{}\n",
+            severity.paint(match &self.rule {
+                Some(rule) => format!("{}[{}]", self.severity, rule),
+                None => self.severity.to_string(),
+            }),
+            style(Style::new().bold()).paint(format!(": {}", self.message))
+        );
+        let location = if is_unknown(&self.span) {
+            self.file.clone()
+        } else {
+            format!("{}:{}", self.file, self.span)
+        };
+        s += &format!("{}{} {}\n", blank, gutter.paint("-->"), location);
+        if line_numbers.is_empty() {
+            return s;
+        }
+
+        s += &format!("{} {}\n", blank, gutter.paint("|"));
+        let mut previous = None;
+        for line_number in line_numbers {
+            if previous.is_some_and(|p| p + 1 < line_number) {
+                s += &format!("{}\n", gutter.paint("..."));
+            }
+            previous = Some(line_number);
+
+            let line = lines[line_number as usize - 1].trim_end_matches('\r');
+            s += &format!(
+                "{} {}\n",
+                gutter.paint(format!("{:>width$} |", line_number, width = width)),
+                expand_tabs(line)
+            );
+
+            for (span, message, marker, marker_style) in &labels {
+                if span.line != line_number {
+                    continue;
+                }
+
+                // Spans across several lines are underlined up to the end of their first line
+                let start = display_width(line, span.column);
+                let end = if span.end_line == span.line {
+                    display_width(line, span.end_column)
+                } else {
+                    display_width(line, u32::MAX)
+                };
+                let markers = marker.to_string().repeat(end.saturating_sub(start).max(1));
+
+                let mut underline = format!("{}{}", " ".repeat(start), markers);
+                if !message.is_empty() {
+                    underline += &format!(" {}", message);
+                }
+                s += &format!(
+                    "{} {}\n",
+                    gutter.paint(format!("{} |", blank)),
+                    marker_style.paint(underline)
+                );
+            }
+        }
+
+        s
+    }
+
+    /// Formats the diagnostic as a workflow command, which GitHub Actions shows as an annotation.
+    pub fn github_annotation(&self) -> String {
+        let mut message = self.message.clone();
+        for note in &self.notes {
+            message += &format!("\n{}: {}", note.span, note.message);
+        }
+
+        let mut properties = Vec::new();
+        if !self.file.is_empty() {
+            properties.push(format!("file={}", escape_property(&self.file)));
+            // Diagnostics about a whole file annotate the file without a line
+            if !is_unknown(&self.span) {
+                properties.push(format!(
+                    "line={},col={},endLine={},endColumn={}",
+                    self.span.line, self.span.column, self.span.end_line, self.span.end_column
+                ));
+            }
+        }
+        if let Some(rule) = &self.rule {
+            properties.push(format!("title={}", escape_property(rule)));
+        }
+
+        let mut command = self.severity.to_string();
+        if !properties.is_empty() {
+            command += &format!(" {}", properties.join(","));
+        }
+        format!("::{}::{}", command, escape_data(&message))
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "{}:{}: {}", self.file, self.span, self.message)
+    }
+}
+
+fn expand_tabs(line: &str) -> String {
+    line.replace('\t', &" ".repeat(TAB_WIDTH))
+}
+
+// Width of the line up to the given column, after expanding tabs
+fn display_width(line: &str, column: u32) -> usize {
+    line.chars()
+        .take(column.saturating_sub(1) as usize)
+        .map(|c| if c == '\t' { TAB_WIDTH } else { 1 })
+        .sum()
+}
+
+fn is_unknown(span: &SourceSpan) -> bool {
+    *span == SourceSpan::default()
+}
+
+// Escaping of workflow commands, see the runner's implementation of ::error
+fn escape_data(s: &str) -> String {
+    s.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+fn escape_property(s: &str) -> String {
+    escape_data(s).replace(':', "%3A").replace(',', "%2C")
+}
+
+fn sarif_location(file: &str, span: &SourceSpan) -> Value {
+    let mut location = json!({
+        "physicalLocation": {
+            "artifactLocation": { "uri": file },
+        },
+    });
+    // Diagnostics about a whole file have no region to point at
+    if !is_unknown(span) {
+        location["physicalLocation"]["region"] = json!({
+            "startLine": span.line,
+            "startColumn": span.column,
+            "endLine": span.end_line,
+            "endColumn": span.end_column,
+        });
+    }
+    location
+}
+
+/// Builds a SARIF 2.1.0 log of the diagnostics, for tools collecting the results of static analysis.
+pub fn sarif_log(diagnostics: &[Diagnostic]) -> Value {
+    let results = diagnostics
+        .iter()
+        .map(|d| {
+            let related = d
+                .notes
+                .iter()
+                .enumerate()
+                .map(|(id, note)| {
+                    let mut location = sarif_location(&d.file, &note.span);
+                    location["id"] = json!(id);
+                    location["message"] = json!({ "text": note.message });
+                    location
+                })
+                .collect::<Vec<_>>();
+
+            let locations = if d.file.is_empty() {
+                Vec::new()
+            } else {
+                vec![sarif_location(&d.file, &d.span)]
+            };
+
+            let mut result = json!({
+                "level": d.severity.to_string(),
+                "message": { "text": d.message },
+                "locations": locations,
+                "relatedLocations": related,
+            });
+            if let Some(rule) = &d.rule {
+                result["ruleId"] = json!(rule);
+            }
+            result
+        })
+        .collect::<Vec<_>>();
+
+    json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "godotdoc",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": "https://github.com/Dragoncraft89/godotdoc",
+                },
+            },
+            // Columns count characters, like everywhere else in godotdoc
+            "columnKind": "unicodeCodePoints",
+            "results": results,
+        }],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sarif_regions() {
+        let span = SourceSpan {
+            line: 3,
+            column: 5,
+            end_line: 3,
+            end_column: 9,
+        };
+        let located = sarif_location("a.gd", &span);
+        assert_eq!(located["physicalLocation"]["region"]["startLine"], 3);
+        assert_eq!(located["physicalLocation"]["region"]["endColumn"], 9);
+
+        let whole_file = sarif_location("a.gd", &SourceSpan::default());
+        assert_eq!(
+            whole_file["physicalLocation"]["artifactLocation"]["uri"],
+            "a.gd"
+        );
+        assert!(whole_file["physicalLocation"].get("region").is_none());
+    }
+
+    #[test]
+    fn github_annotations() {
+        let span = SourceSpan {
+            line: 3,
+            column: 5,
+            end_line: 3,
+            end_column: 9,
+        };
+        let located = Diagnostic::error("a,b.gd", span, "100% wrong\nreally".to_string())
+            .with_rule("duplicate_symbol");
+        assert_eq!(
+            located.github_annotation(),
+            "::error file=a%2Cb.gd,line=3,col=5,endLine=3,endColumn=9,title=duplicate_symbol\
+             ::100%25 wrong%0Areally"
+        );
+
+        let whole_file =
+            Diagnostic::warning("addons", SourceSpan::default(), "changed".to_string());
+        assert_eq!(
+            whole_file.github_annotation(),
+            "::warning file=addons::changed"
+        );
+
+        let no_file = Diagnostic::error("", SourceSpan::default(), "failed".to_string());
+        assert_eq!(no_file.github_annotation(), "::error::failed");
+    }
+
+    #[test]
+    fn source_excerpts() {
+        let span = |line, column, end_column| SourceSpan {
+            line,
+            column,
+            end_line: line,
+            end_column,
+        };
+        let diagnostic = Diagnostic::warning(
+            "player.gd",
+            span(3, 5, 10),
+            "'speed' is declared twice".to_string(),
+        )
+        .with_rule("duplicate_symbol")
+        .with_note(span(1, 6, 11), "first declared here");
+        let source = "\tvar speed\nvar jump\nvar speed\n";
+        assert_eq!(
+            diagnostic.render(Some(source), false),
+            "warning[duplicate_symbol]: 'speed' is declared twice\n \
+             --> player.gd:3:5\n  \
+             |\n\
+             1 |     var speed\n  \
+             |         ----- first declared here\n\
+             ...\n\
+             3 | var speed\n  \
+             |     ^^^^^\n"
+        );
+
+        // Without the source, only the location is printed
+        assert_eq!(
+            diagnostic.render(None, false),
+            "warning[duplicate_symbol]: 'speed' is declared twice\n--> player.gd:3:5\n"
+        );
+        assert!(diagnostic.render(None, true).contains("\u{1b}["));
+    }
+}
@@ -0,0 +1,1013 @@
+use crate::parser::{
+    Annotation, DocumentationData, DocumentationEntry, EntryType, ExportHint, FunctionArgument,
+    Symbol, SymbolArgs,
+};
+use crate::project::Version;
+
+use serde::Serialize;
+
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::io::Write;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+/// The part of the version, which has to be incremented for a change according to semver.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Bump {
+    None,
+    Patch,
+    Minor,
+    Major,
+}
+
+impl Display for Bump {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            Bump::None => write!(f, "none"),
+            Bump::Patch => write!(f, "patch"),
+            Bump::Minor => write!(f, "minor"),
+            Bump::Major => write!(f, "major"),
+        }
+    }
+}
+
+impl Bump {
+    /// The bump recorded by going from one version to the other. Before 1.0.0, the minor version
+    /// is incremented for breaking changes and the patch version for compatible ones.
+    pub fn between(old: &Version, new: &Version) -> Bump {
+        let unstable = old.major == 0 && new.major == 0;
+        if new.major != old.major || (unstable && new.minor != old.minor) {
+            Bump::Major
+        } else if new.minor != old.minor || (unstable && new.patch != old.patch) {
+            Bump::Minor
+        } else if new.patch != old.patch {
+            Bump::Patch
+        } else {
+            Bump::None
+        }
+    }
+}
+
+/// What changed about a symbol present in both versions.
+#[derive(PartialEq, Debug, Serialize)]
+#[serde(tag = "change", rename_all = "snake_case")]
+pub enum Detail {
+    ArgumentAdded {
+        name: String,
+        optional: bool,
+    },
+    ArgumentRemoved {
+        name: String,
+    },
+    ArgumentRenamed {
+        old: String,
+        new: String,
+    },
+    ArgumentTypeChanged {
+        name: String,
+        old: Option<String>,
+        new: Option<String>,
+    },
+    ArgumentDefaultChanged {
+        name: String,
+        old: Option<String>,
+        new: Option<String>,
+    },
+    ReturnTypeChanged {
+        old: Option<String>,
+        new: Option<String>,
+    },
+    TypeChanged {
+        old: Option<String>,
+        new: Option<String>,
+    },
+    ValueChanged {
+        old: Option<String>,
+        new: Option<String>,
+    },
+    HintChanged {
+        old: String,
+        new: String,
+    },
+    EnumValueAdded {
+        name: String,
+        value: isize,
+    },
+    EnumValueRemoved {
+        name: String,
+        value: isize,
+    },
+    EnumValueChanged {
+        name: String,
+        old: isize,
+        new: isize,
+    },
+    ExtendsChanged {
+        old: Option<String>,
+        new: Option<String>,
+    },
+    ClassNameChanged {
+        old: Option<String>,
+        new: Option<String>,
+    },
+}
+
+impl Detail {
+    /// Whether the change breaks scripts and scenes using the old version.
+    pub fn bump(&self) -> Bump {
+        match self {
+            Detail::ArgumentAdded { optional, .. } if *optional => Bump::Minor,
+            // Callers may still pass any value to an argument which lost its type
+            Detail::ArgumentTypeChanged { new: None, .. } => Bump::Minor,
+            // Typing an untyped return value is a tightening, but callers can still use the value
+            Detail::ReturnTypeChanged { old: None, .. } => Bump::Minor,
+            // The argument became optional
+            Detail::ArgumentDefaultChanged { new: Some(_), .. } => Bump::Minor,
+            Detail::ArgumentRenamed { .. } => Bump::Patch,
+            Detail::ValueChanged { .. } | Detail::HintChanged { .. } => Bump::Minor,
+            Detail::EnumValueAdded { .. } => Bump::Minor,
+            // Scenes store the numbers of enum values
+            Detail::EnumValueChanged { .. } | Detail::EnumValueRemoved { .. } => Bump::Major,
+            Detail::ArgumentAdded { .. }
+            | Detail::ArgumentRemoved { .. }
+            | Detail::ArgumentTypeChanged { .. }
+            | Detail::ArgumentDefaultChanged { .. }
+            | Detail::ReturnTypeChanged { .. }
+            | Detail::TypeChanged { .. }
+            | Detail::ExtendsChanged { .. }
+            | Detail::ClassNameChanged { .. } => Bump::Major,
+        }
+    }
+}
+
+// Formats an optional type or value for the report
+fn code(x: &Option<String>) -> String {
+    match x {
+        Some(x) if !x.is_empty() => format!("`{}`", x),
+        Some(_) => "inferred".to_string(),
+        None => "none".to_string(),
+    }
+}
+
+impl Display for Detail {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            Detail::ArgumentAdded { name, optional } => {
+                write!(f, "added argument `{}`", name)?;
+                if *optional {
+                    write!(f, " (optional)")?;
+                }
+                Ok(())
+            }
+            Detail::ArgumentRemoved { name } => write!(f, "removed argument `{}`", name),
+            Detail::ArgumentRenamed { old, new } => {
+                write!(f, "renamed argument `{}` to `{}`", old, new)
+            }
+            Detail::ArgumentTypeChanged { name, old, new } => write!(
+                f,
+                "changed the type of argument `{}` from {} to {}",
+                name,
+                code(old),
+                code(new)
+            ),
+            Detail::ArgumentDefaultChanged { name, old, new } => write!(
+                f,
+                "changed the default value of argument `{}` from {} to {}",
+                name,
+                code(old),
+                code(new)
+            ),
+            Detail::ReturnTypeChanged { old, new } => write!(
+                f,
+                "changed the return type from {} to {}",
+                code(old),
+                code(new)
+            ),
+            Detail::TypeChanged { old, new } => {
+                write!(f, "changed the type from {} to {}", code(old), code(new))
+            }
+            Detail::ValueChanged { old, new } => {
+                write!(f, "changed the value from {} to {}", code(old), code(new))
+            }
+            Detail::HintChanged { old, new } => write!(
+                f,
+                "changed the export hint from {} to {}",
+                code(&Some(old.clone())),
+                code(&Some(new.clone()))
+            ),
+            Detail::EnumValueAdded { name, value } => {
+                write!(f, "added value `{} = {}`", name, value)
+            }
+            Detail::EnumValueRemoved { name, value } => {
+                write!(f, "removed value `{} = {}`", name, value)
+            }
+            Detail::EnumValueChanged { name, old, new } => {
+                write!(f, "renumbered value `{}` from {} to {}", name, old, new)
+            }
+            Detail::ExtendsChanged { old, new } => write!(
+                f,
+                "changed the base class from {} to {}",
+                code(old),
+                code(new)
+            ),
+            Detail::ClassNameChanged { old, new } => write!(
+                f,
+                "changed the class_name from {} to {}",
+                code(old),
+                code(new)
+            ),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct Change {
+    pub kind: ChangeKind,
+    pub entry_type: EntryType,
+    // Name of the symbol, prefixed by the inner classes declaring it
+    pub name: String,
+    pub old: Option<String>,
+    pub new: Option<String>,
+    pub details: Vec<Detail>,
+    pub bump: Bump,
+}
+
+#[derive(Serialize)]
+pub struct ScriptChanges {
+    pub path: String,
+    pub kind: ChangeKind,
+    pub bump: Bump,
+    // Changes of the script header
+    pub details: Vec<Detail>,
+    pub changes: Vec<Change>,
+}
+
+#[derive(Serialize)]
+pub struct ApiDiff {
+    // Minimum bump of the version for all changes
+    pub bump: Bump,
+    // Versions of the addon, as recorded in plugin.cfg
+    pub old_version: Option<String>,
+    pub new_version: Option<String>,
+    pub scripts: Vec<ScriptChanges>,
+}
+
+fn max_bump<'a>(details: impl Iterator<Item = &'a Detail>) -> Bump {
+    details.map(Detail::bump).max().unwrap_or(Bump::None)
+}
+
+// Private symbols are not part of the API, except for the constructor
+fn is_public(name: &str) -> bool {
+    !name.starts_with('_') || name == "_init"
+}
+
+fn signature(name: &str, symbol: &Symbol) -> String {
+    let mut s = name.to_string();
+    match &symbol.arg {
+        Some(SymbolArgs::FunctionArgs(function)) => {
+            let arguments = function
+                .arguments
+                .iter()
+                .map(|a| a.to_string())
+                .collect::<Vec<_>>();
+            s += &format!("({})", arguments.join(", "));
+            if let Some(return_type) = &function.return_type {
+                s += &format!(" -> {}", return_type);
+            }
+        }
+        Some(SymbolArgs::VariableArgs(variable)) => {
+            if let Some(value_type) = variable.value_type.as_ref().filter(|t| !t.is_empty()) {
+                s += &format!(": {}", value_type);
+            }
+            if let Some(assignment) = &variable.assignment {
+                s += &format!(" = {}", assignment);
+            }
+        }
+        Some(SymbolArgs::ExportArgs(export)) => {
+            if let Some(value_type) = export.value_type.as_ref().filter(|t| !t.is_empty()) {
+                s += &format!(": {}", value_type);
+            }
+            if let Some(assignment) = &export.assignment {
+                s += &format!(" = {}", assignment);
+            }
+        }
+        Some(SymbolArgs::SignalArgs(arguments)) if !arguments.is_empty() => {
+            let arguments = arguments.iter().map(|a| a.to_string()).collect::<Vec<_>>();
+            s += &format!("({})", arguments.join(", "));
+        }
+        Some(SymbolArgs::EnumArgs(values)) => {
+            let values = values
+                .iter()
+                .map(|v| format!("{} = {}", v.name, v.value))
+                .collect::<Vec<_>>();
+            s += &format!(" {{ {} }}", values.join(", "));
+        }
+        Some(SymbolArgs::ClassArgs(class)) => {
+            if let Some(extends) = &class.extends {
+                s += &format!(" extends {}", extends);
+            }
+        }
+        Some(SymbolArgs::SignalArgs(_)) | None => (),
+    }
+
+    s
+}
+
+// Collects the public symbols of a script, including the members of inner classes
+fn collect_symbols<'a>(
+    prefix: &str,
+    entries: &'a [DocumentationEntry],
+    symbols: &mut Vec<(EntryType, String, &'a Symbol)>,
+) {
+    for entry in entries {
+        for symbol in entry.symbols.iter().filter(|s| is_public(&s.name)) {
+            let name = format!("{}{}", prefix, symbol.name);
+            if let Some(SymbolArgs::ClassArgs(class)) = &symbol.arg {
+                symbols.push((entry.entry_type, name.clone(), symbol));
+                collect_symbols(&format!("{}.", name), &class.entries, symbols);
+            } else {
+                symbols.push((entry.entry_type, name, symbol));
+            }
+        }
+    }
+}
+
+fn is_optional(argument: &FunctionArgument) -> bool {
+    argument.default_value.is_some()
+}
+
+// Compares arguments by position, as GDScript passes them
+fn compare_arguments(
+    old: &[FunctionArgument],
+    new: &[FunctionArgument],
+    details: &mut Vec<Detail>,
+) {
+    for (old, new) in old.iter().zip(new) {
+        if old.name != new.name {
+            details.push(Detail::ArgumentRenamed {
+                old: old.name.clone(),
+                new: new.name.clone(),
+            });
+        }
+        if old.value_type != new.value_type {
+            details.push(Detail::ArgumentTypeChanged {
+                name: new.name.clone(),
+                old: old.value_type.clone(),
+                new: new.value_type.clone(),
+            });
+        }
+        if old.default_value != new.default_value {
+            details.push(Detail::ArgumentDefaultChanged {
+                name: new.name.clone(),
+                old: old.default_value.clone(),
+                new: new.default_value.clone(),
+            });
+        }
+    }
+
+    for argument in new.iter().skip(old.len()) {
+        details.push(Detail::ArgumentAdded {
+            name: argument.name.clone(),
+            optional: is_optional(argument),
+        });
+    }
+    for argument in old.iter().skip(new.len()) {
+        details.push(Detail::ArgumentRemoved {
+            name: argument.name.clone(),
+        });
+    }
+}
+
+fn export_hint(hint: &Option<ExportHint>, annotations: &[Annotation]) -> String {
+    hint.iter()
+        .map(|h| h.to_string())
+        .chain(annotations.iter().map(|a| a.to_string()))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn compare_symbols(old: &Symbol, new: &Symbol) -> Vec<Detail> {
+    let mut details = Vec::new();
+    match (&old.arg, &new.arg) {
+        (Some(SymbolArgs::SignalArgs(old)), Some(SymbolArgs::SignalArgs(new))) => {
+            compare_arguments(old, new, &mut details);
+        }
+        (Some(SymbolArgs::FunctionArgs(old)), Some(SymbolArgs::FunctionArgs(new))) => {
+            compare_arguments(&old.arguments, &new.arguments, &mut details);
+            if old.return_type != new.return_type {
+                details.push(Detail::ReturnTypeChanged {
+                    old: old.return_type.clone(),
+                    new: new.return_type.clone(),
+                });
+            }
+        }
+        (Some(SymbolArgs::VariableArgs(old)), Some(SymbolArgs::VariableArgs(new))) => {
+            if old.value_type != new.value_type {
+                details.push(Detail::TypeChanged {
+                    old: old.value_type.clone(),
+                    new: new.value_type.clone(),
+                });
+            }
+            if old.assignment != new.assignment {
+                details.push(Detail::ValueChanged {
+                    old: old.assignment.clone(),
+                    new: new.assignment.clone(),
+                });
+            }
+        }
+        (Some(SymbolArgs::ExportArgs(old)), Some(SymbolArgs::ExportArgs(new))) => {
+            if old.value_type != new.value_type {
+                details.push(Detail::TypeChanged {
+                    old: old.value_type.clone(),
+                    new: new.value_type.clone(),
+                });
+            }
+            if old.assignment != new.assignment {
+                details.push(Detail::ValueChanged {
+                    old: old.assignment.clone(),
+                    new: new.assignment.clone(),
+                });
+            }
+
+            let old_hint = export_hint(&old.hint, &old.annotations);
+            let new_hint = export_hint(&new.hint, &new.annotations);
+            if old_hint != new_hint {
+                details.push(Detail::HintChanged {
+                    old: old_hint,
+                    new: new_hint,
+                });
+            }
+        }
+        (Some(SymbolArgs::EnumArgs(old)), Some(SymbolArgs::EnumArgs(new))) => {
+            for value in old {
+                match new.iter().find(|v| v.name == value.name) {
+                    Some(new_value) if new_value.value != value.value => {
+                        details.push(Detail::EnumValueChanged {
+                            name: value.name.clone(),
+                            old: value.value,
+                            new: new_value.value,
+                        })
+                    }
+                    Some(_) => (),
+                    None => details.push(Detail::EnumValueRemoved {
+                        name: value.name.clone(),
+                        value: value.value,
+                    }),
+                }
+            }
+            for value in new.iter().filter(|v| !old.iter().any(|o| o.name == v.name)) {
+                details.push(Detail::EnumValueAdded {
+                    name: value.name.clone(),
+                    value: value.value,
+                });
+            }
+        }
+        (Some(SymbolArgs::ClassArgs(old)), Some(SymbolArgs::ClassArgs(new)))
+            if old.extends != new.extends =>
+        {
+            details.push(Detail::ExtendsChanged {
+                old: old.extends.clone(),
+                new: new.extends.clone(),
+            });
+        }
+        _ => (),
+    }
+
+    details
+}
+
+fn compare_scripts(path: &str, old: &DocumentationData, new: &DocumentationData) -> ScriptChanges {
+    let mut details = Vec::new();
+    if old.class_name != new.class_name {
+        details.push(Detail::ClassNameChanged {
+            old: old.class_name.clone(),
+            new: new.class_name.clone(),
+        });
+    }
+    if old.extends != new.extends {
+        details.push(Detail::ExtendsChanged {
+            old: old.extends.clone(),
+            new: new.extends.clone(),
+        });
+    }
+
+    let mut old_symbols = Vec::new();
+    collect_symbols("", &old.entries, &mut old_symbols);
+    let mut new_symbols = Vec::new();
+    collect_symbols("", &new.entries, &mut new_symbols);
+    let new_index = new_symbols
+        .iter()
+        .map(|(entry_type, name, symbol)| ((*entry_type, name.as_str()), *symbol))
+        .collect::<HashMap<_, _>>();
+
+    let mut changes = Vec::new();
+    for (entry_type, name, old_symbol) in &old_symbols {
+        let change = match new_index.get(&(*entry_type, name.as_str())) {
+            Some(new_symbol) => {
+                let details = compare_symbols(old_symbol, new_symbol);
+                if details.is_empty() {
+                    continue;
+                }
+                Change {
+                    kind: ChangeKind::Changed,
+                    entry_type: *entry_type,
+                    name: name.clone(),
+                    old: Some(signature(name, old_symbol)),
+                    new: Some(signature(name, new_symbol)),
+                    bump: max_bump(details.iter()),
+                    details,
+                }
+            }
+            None => Change {
+                kind: ChangeKind::Removed,
+                entry_type: *entry_type,
+                name: name.clone(),
+                old: Some(signature(name, old_symbol)),
+                new: None,
+                details: Vec::new(),
+                bump: Bump::Major,
+            },
+        };
+        changes.push(change);
+    }
+
+    for (entry_type, name, new_symbol) in &new_symbols {
+        if !old_symbols
+            .iter()
+            .any(|(t, n, _)| t == entry_type && n == name)
+        {
+            changes.push(Change {
+                kind: ChangeKind::Added,
+                entry_type: *entry_type,
+                name: name.clone(),
+                old: None,
+                new: Some(signature(name, new_symbol)),
+                details: Vec::new(),
+                bump: Bump::Minor,
+            });
+        }
+    }
+
+    let bump = changes
+        .iter()
+        .map(|c| c.bump)
+        .chain([max_bump(details.iter())])
+        .max()
+        .unwrap();
+    ScriptChanges {
+        path: path.to_string(),
+        kind: ChangeKind::Changed,
+        bump,
+        details,
+        changes,
+    }
+}
+
+/// Compares the public API of two versions of a project, given the scripts of each
+/// version by their path.
+pub fn diff(old: &[(String, DocumentationData)], new: &[(String, DocumentationData)]) -> ApiDiff {
+    let mut scripts = Vec::new();
+    for (path, old_data) in old {
+        match new.iter().find(|(p, _)| p == path) {
+            Some((_, new_data)) => {
+                let changes = compare_scripts(path, old_data, new_data);
+                if !changes.details.is_empty() || !changes.changes.is_empty() {
+                    scripts.push(changes);
+                }
+            }
+            None => scripts.push(ScriptChanges {
+                path: path.clone(),
+                kind: ChangeKind::Removed,
+                bump: Bump::Major,
+                details: Vec::new(),
+                changes: Vec::new(),
+            }),
+        }
+    }
+
+    for (path, _) in new.iter().filter(|(p, _)| !old.iter().any(|(o, _)| o == p)) {
+        scripts.push(ScriptChanges {
+            path: path.clone(),
+            kind: ChangeKind::Added,
+            bump: Bump::Minor,
+            details: Vec::new(),
+            changes: Vec::new(),
+        });
+    }
+
+    ApiDiff {
+        bump: scripts.iter().map(|s| s.bump).max().unwrap_or(Bump::None),
+        old_version: None,
+        new_version: None,
+        scripts,
+    }
+}
+
+fn breaking(bump: Bump) -> &'static str {
+    if bump == Bump::Major {
+        " **(breaking)**"
+    } else {
+        ""
+    }
+}
+
+impl ApiDiff {
+    pub fn write_markdown(&self, f: &mut dyn Write) -> std::io::Result<()> {
+        writeln!(f, "# API changes")?;
+        if let (Some(old), Some(new)) = (&self.old_version, &self.new_version) {
+            writeln!(f, "\nVersion {} to {}", old, new)?;
+        }
+        writeln!(f, "\nSuggested version bump: **{}**", self.bump)?;
+        if self.scripts.is_empty() {
+            writeln!(f, "\nNo changes to the public API.")?;
+        }
+
+        for script in &self.scripts {
+            writeln!(f, "\n## {}", script.path)?;
+            match script.kind {
+                ChangeKind::Added => writeln!(f, "Added script.")?,
+                ChangeKind::Removed => writeln!(f, "Removed script.{}", breaking(script.bump))?,
+                ChangeKind::Changed => (),
+            }
+            for detail in &script.details {
+                writeln!(f, "* Script: {}{}", detail, breaking(detail.bump()))?;
+            }
+
+            for change in &script.changes {
+                let kind = change.entry_type.singular();
+                match change.kind {
+                    ChangeKind::Added => writeln!(
+                        f,
+                        "* Added {} `{}`",
+                        kind,
+                        change.new.as_deref().unwrap_or_default()
+                    )?,
+                    ChangeKind::Removed => writeln!(
+                        f,
+                        "* Removed {} `{}`{}",
+                        kind,
+                        change.old.as_deref().unwrap_or_default(),
+                        breaking(change.bump)
+                    )?,
+                    ChangeKind::Changed => {
+                        writeln!(
+                            f,
+                            "* Changed {} `{}`",
+                            kind,
+                            change.new.as_deref().unwrap_or_default()
+                        )?;
+                        for detail in &change.details {
+                            writeln!(f, "    * {}{}", detail, breaking(detail.bump()))?;
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::parse_script;
+
+    fn compare(old: &str, new: &str) -> ScriptChanges {
+        let (old, diagnostics) = parse_script(old);
+        assert!(diagnostics.is_empty());
+        let (new, diagnostics) = parse_script(new);
+        assert!(diagnostics.is_empty());
+        compare_scripts("test.gd", &old, &new)
+    }
+
+    fn some(s: &str) -> Option<String> {
+        Some(s.to_string())
+    }
+
+    fn version(major: u64, minor: u64, patch: u64) -> Version {
+        Version {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    #[test]
+    fn bump_between_versions() {
+        let between = |old, new| Bump::between(&old, &new);
+        assert_eq!(between(version(1, 2, 3), version(2, 0, 0)), Bump::Major);
+        assert_eq!(between(version(1, 2, 3), version(1, 3, 0)), Bump::Minor);
+        assert_eq!(between(version(1, 2, 3), version(1, 2, 4)), Bump::Patch);
+        assert_eq!(between(version(1, 2, 3), version(1, 2, 3)), Bump::None);
+        assert_eq!(between(version(0, 9, 1), version(1, 0, 0)), Bump::Major);
+    }
+
+    #[test]
+    fn bump_before_1_0_0() {
+        // The minor version counts as major, the patch version as minor
+        let between = |old, new| Bump::between(&old, &new);
+        assert_eq!(between(version(0, 2, 3), version(0, 3, 0)), Bump::Major);
+        assert_eq!(between(version(0, 2, 3), version(0, 2, 4)), Bump::Minor);
+        assert_eq!(between(version(0, 2, 3), version(0, 2, 3)), Bump::None);
+    }
+
+    #[test]
+    fn bump_of_details() {
+        let name = || "a".to_string();
+        let cases = [
+            (
+                Detail::ArgumentAdded {
+                    name: name(),
+                    optional: true,
+                },
+                Bump::Minor,
+            ),
+            (
+                Detail::ArgumentAdded {
+                    name: name(),
+                    optional: false,
+                },
+                Bump::Major,
+            ),
+            (Detail::ArgumentRemoved { name: name() }, Bump::Major),
+            (
+                Detail::ArgumentRenamed {
+                    old: name(),
+                    new: name(),
+                },
+                Bump::Patch,
+            ),
+            (
+                Detail::ArgumentTypeChanged {
+                    name: name(),
+                    old: some("int"),
+                    new: None,
+                },
+                Bump::Minor,
+            ),
+            (
+                Detail::ArgumentTypeChanged {
+                    name: name(),
+                    old: None,
+                    new: some("int"),
+                },
+                Bump::Major,
+            ),
+            (
+                Detail::ArgumentDefaultChanged {
+                    name: name(),
+                    old: None,
+                    new: some("1"),
+                },
+                Bump::Minor,
+            ),
+            (
+                Detail::ArgumentDefaultChanged {
+                    name: name(),
+                    old: some("1"),
+                    new: None,
+                },
+                Bump::Major,
+            ),
+            (
+                Detail::ReturnTypeChanged {
+                    old: None,
+                    new: some("int"),
+                },
+                Bump::Minor,
+            ),
+            (
+                Detail::ReturnTypeChanged {
+                    old: some("int"),
+                    new: None,
+                },
+                Bump::Major,
+            ),
+            (
+                Detail::TypeChanged {
+                    old: some("int"),
+                    new: some("float"),
+                },
+                Bump::Major,
+            ),
+            (
+                Detail::ValueChanged {
+                    old: some("1"),
+                    new: some("2"),
+                },
+                Bump::Minor,
+            ),
+            (
+                Detail::HintChanged {
+                    old: name(),
+                    new: name(),
+                },
+                Bump::Minor,
+            ),
+            (
+                Detail::EnumValueAdded {
+                    name: name(),
+                    value: 1,
+                },
+                Bump::Minor,
+            ),
+            (
+                Detail::EnumValueRemoved {
+                    name: name(),
+                    value: 1,
+                },
+                Bump::Major,
+            ),
+            (
+                Detail::EnumValueChanged {
+                    name: name(),
+                    old: 1,
+                    new: 2,
+                },
+                Bump::Major,
+            ),
+            (
+                Detail::ExtendsChanged {
+                    old: some("Node"),
+                    new: some("Node2D"),
+                },
+                Bump::Major,
+            ),
+            (
+                Detail::ClassNameChanged {
+                    old: some("A"),
+                    new: some("B"),
+                },
+                Bump::Major,
+            ),
+        ];
+        for (detail, bump) in cases {
+            assert_eq!(detail.bump(), bump, "{:?}", detail);
+        }
+    }
+
+    #[test]
+    fn arguments_are_compared_by_position() {
+        let changes = compare(
+            "func f(a, b: int, c = 1, d):\n\tpass\n",
+            "func f(x, b: float, c = 2):\n\tpass\n",
+        );
+        assert_eq!(changes.changes.len(), 1);
+        let change = &changes.changes[0];
+        assert_eq!(change.kind, ChangeKind::Changed);
+        assert_eq!(change.name, "f");
+        assert_eq!(
+            change.details,
+            vec![
+                Detail::ArgumentRenamed {
+                    old: "a".to_string(),
+                    new: "x".to_string(),
+                },
+                Detail::ArgumentTypeChanged {
+                    name: "b".to_string(),
+                    old: some("int"),
+                    new: some("float"),
+                },
+                Detail::ArgumentDefaultChanged {
+                    name: "c".to_string(),
+                    old: some("1"),
+                    new: some("2"),
+                },
+                Detail::ArgumentRemoved {
+                    name: "d".to_string(),
+                },
+            ]
+        );
+        assert_eq!(change.bump, Bump::Major);
+    }
+
+    #[test]
+    fn compatible_argument_changes() {
+        let changes = compare(
+            "func f(a: int, b):\n\tpass\n",
+            "func f(a, b = 0, c = 1) -> int:\n\tpass\n",
+        );
+        let change = &changes.changes[0];
+        assert_eq!(
+            change.details,
+            vec![
+                Detail::ArgumentTypeChanged {
+                    name: "a".to_string(),
+                    old: some("int"),
+                    new: None,
+                },
+                Detail::ArgumentDefaultChanged {
+                    name: "b".to_string(),
+                    old: None,
+                    new: some("0"),
+                },
+                Detail::ArgumentAdded {
+                    name: "c".to_string(),
+                    optional: true,
+                },
+                Detail::ReturnTypeChanged {
+                    old: None,
+                    new: some("int"),
+                },
+            ]
+        );
+        assert_eq!(change.bump, Bump::Minor);
+    }
+
+    #[test]
+    fn enum_values_are_compared_by_name() {
+        let changes = compare("enum E {A, B, C = 5}\n", "enum E {A, C = 6, D}\n");
+        let change = &changes.changes[0];
+        assert_eq!(
+            change.details,
+            vec![
+                Detail::EnumValueRemoved {
+                    name: "B".to_string(),
+                    value: 1,
+                },
+                Detail::EnumValueChanged {
+                    name: "C".to_string(),
+                    old: 5,
+                    new: 6,
+                },
+                Detail::EnumValueAdded {
+                    name: "D".to_string(),
+                    value: 7,
+                },
+            ]
+        );
+        assert_eq!(change.bump, Bump::Major);
+
+        let changes = compare("enum E {A}\n", "enum E {A, B}\n");
+        assert_eq!(changes.bump, Bump::Minor);
+    }
+
+    #[test]
+    fn classes_and_their_members() {
+        let changes = compare(
+            "class_name Old\nclass Inner extends Node:\n\tvar x = 1\n\tvar _hidden\n",
+            "class_name New\nclass Inner extends Node2D:\n\tvar y = 1\n",
+        );
+        assert_eq!(
+            changes.details,
+            vec![Detail::ClassNameChanged {
+                old: some("Old"),
+                new: some("New"),
+            }]
+        );
+
+        let changes = changes
+            .changes
+            .iter()
+            .map(|c| (c.kind, c.name.as_str(), &c.details))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            changes,
+            vec![
+                (
+                    ChangeKind::Changed,
+                    "Inner",
+                    &vec![Detail::ExtendsChanged {
+                        old: some("Node"),
+                        new: some("Node2D"),
+                    }]
+                ),
+                (ChangeKind::Removed, "Inner.x", &vec![]),
+                (ChangeKind::Added, "Inner.y", &vec![]),
+            ]
+        );
+    }
+
+    #[test]
+    fn scripts_are_matched_by_path() {
+        let (a, _) = parse_script("var a\n");
+        let (b, _) = parse_script("var a\n");
+        let (c, _) = parse_script("var c\n");
+        let old = vec![("a.gd".to_string(), a), ("b.gd".to_string(), b)];
+        let new = vec![("b.gd".to_string(), c)];
+
+        let api = diff(&old, &new);
+        let scripts = api
+            .scripts
+            .iter()
+            .map(|s| (s.path.as_str(), s.kind, s.bump))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            scripts,
+            vec![
+                ("a.gd", ChangeKind::Removed, Bump::Major),
+                ("b.gd", ChangeKind::Changed, Bump::Major),
+            ]
+        );
+        assert_eq!(api.bump, Bump::Major);
+    }
+}
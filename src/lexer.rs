@@ -0,0 +1,437 @@
+use crate::diagnostic::Diagnostic;
+use crate::parser::SourceSpan;
+
+// Operators consisting of more than one character, longest first
+const OPERATORS: &[&str] = &[
+    "**=", "<<=", ">>=", "**", "<<", ">>", "==", "!=", "<=", ">=", "&&", "||", "+=", "-=", "*=",
+    "/=", "%=", "&=", "|=", "^=", "->", ":=", "..",
+];
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TokenKind {
+    Identifier,
+    Number,
+    String,
+    Symbol,
+    Comment,
+    // End of a statement, newlines inside of brackets or after a backslash don't end statements
+    Newline,
+}
+
+// Byte offsets into the source of a file
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Token<'a> {
+    pub kind: TokenKind,
+    pub text: &'a str,
+    pub span: Span,
+    // Line and column the token starts at, columns count characters starting from 1
+    pub line: u32,
+    pub column: u32,
+    // Line and column just past the end of the token
+    pub end_line: u32,
+    pub end_column: u32,
+}
+
+impl<'a> Token<'a> {
+    pub fn is(&self, kind: TokenKind, text: &str) -> bool {
+        self.kind == kind && self.text == text
+    }
+
+    pub fn is_symbol(&self, text: &str) -> bool {
+        self.is(TokenKind::Symbol, text)
+    }
+
+    pub fn is_keyword(&self, text: &str) -> bool {
+        self.is(TokenKind::Identifier, text)
+    }
+}
+
+struct Lexer<'a, 'b> {
+    filename: &'a str,
+    source: &'a str,
+    pos: usize,
+    line: u32,
+    column: u32,
+    // Open brackets along with where they were opened
+    brackets: Vec<(char, SourceSpan)>,
+    tokens: Vec<Token<'a>>,
+    diagnostics: &'b mut Vec<Diagnostic>,
+}
+
+impl<'a, 'b> Lexer<'a, 'b> {
+    fn peek(&self) -> Option<char> {
+        self.source[self.pos..].chars().next()
+    }
+
+    fn peek_nth(&self, n: usize) -> Option<char> {
+        self.source[self.pos..].chars().nth(n)
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    fn bump_while(&mut self, predicate: impl Fn(char) -> bool) {
+        while self.peek().is_some_and(&predicate) {
+            self.bump();
+        }
+    }
+
+    // Span from the given position to the current one
+    fn span_from(&self, (line, column): (u32, u32)) -> SourceSpan {
+        SourceSpan {
+            line,
+            column,
+            end_line: self.line,
+            end_column: self.column,
+        }
+    }
+
+    fn error(&mut self, span: SourceSpan, message: &str) {
+        self.diagnostics
+            .push(Diagnostic::error(self.filename, span, message.to_string()));
+    }
+
+    fn push(&mut self, kind: TokenKind, start: usize, (line, column): (u32, u32)) {
+        self.tokens.push(Token {
+            kind,
+            text: &self.source[start..self.pos],
+            span: Span {
+                start,
+                end: self.pos,
+            },
+            line,
+            column,
+            end_line: self.line,
+            end_column: self.column,
+        });
+    }
+
+    // Unterminated strings end at the end of the line
+    fn string(&mut self, quote: char, raw: bool, start: (u32, u32)) {
+        let triple = self.peek() == Some(quote) && self.peek_nth(1) == Some(quote);
+        if triple {
+            self.bump();
+            self.bump();
+        }
+
+        loop {
+            match self.peek() {
+                Some('\n') if !triple => break,
+                None => break,
+                Some('\\') if !raw => {
+                    self.bump();
+                    self.bump();
+                }
+                Some(c) if c == quote => {
+                    self.bump();
+                    if !triple {
+                        return;
+                    }
+                    if self.peek() == Some(quote) && self.peek_nth(1) == Some(quote) {
+                        self.bump();
+                        self.bump();
+                        return;
+                    }
+                }
+                Some(_) => {
+                    self.bump();
+                }
+            }
+        }
+
+        self.error(self.span_from(start), "unterminated string");
+    }
+
+    fn number(&mut self) {
+        let start = self.pos;
+        let mut last = ' ';
+
+        while let Some(c) = self.peek() {
+            let hex =
+                self.source[start..].starts_with("0x") || self.source[start..].starts_with("0X");
+            let exponent_sign = !hex && (c == '+' || c == '-') && (last == 'e' || last == 'E');
+
+            if c == '.' {
+                // Method calls on number literals like 1.abs()
+                if !self.peek_nth(1).is_some_and(|n| n.is_ascii_digit()) {
+                    break;
+                }
+            } else if !(c.is_alphanumeric() || c == '_' || exponent_sign) {
+                break;
+            }
+
+            last = c;
+            self.bump();
+        }
+    }
+
+    // Mismatched brackets still close the innermost open bracket, extra ones are ignored
+    fn close_bracket(&mut self, c: char, span: SourceSpan) {
+        let open = match c {
+            ')' => '(',
+            ']' => '[',
+            _ => '{',
+        };
+
+        match self.brackets.pop() {
+            Some((x, _)) if x == open => (),
+            Some((x, opening)) => {
+                let note = match x {
+                    '(' => "opening parenthesis here",
+                    '[' => "opening bracket here",
+                    _ => "opening brace here",
+                };
+                self.diagnostics.push(
+                    Diagnostic::error(
+                        self.filename,
+                        span,
+                        "Closing parentheses does not match opening parentheses".to_string(),
+                    )
+                    .with_note(opening, note),
+                );
+            }
+            None => self.error(span, &format!("extra '{}'", c)),
+        }
+    }
+
+    fn tokenize(mut self) -> Vec<Token<'a>> {
+        while let Some(c) = self.peek() {
+            let start = self.pos;
+            let position = (self.line, self.column);
+
+            match c {
+                '\n' => {
+                    self.bump();
+                    if self.brackets.is_empty() {
+                        self.push(TokenKind::Newline, start, position);
+                    }
+                }
+                x if x.is_whitespace() => {
+                    self.bump();
+                }
+                '\\' if matches!(self.peek_nth(1), Some('\n') | Some('\r')) => {
+                    // Backslashes at the end of a line ignore the newline
+                    self.bump();
+                    self.bump_while(|c| c == '\r');
+                    if self.bump().is_none() {
+                        let span = self.span_from(position);
+                        self.error(span, "Unexpected eof, expected newline after \\");
+                    }
+                }
+                '#' => {
+                    self.bump_while(|c| c != '\n');
+                    self.push(TokenKind::Comment, start, position);
+                }
+                '"' | '\'' => {
+                    self.bump();
+                    self.string(c, false, position);
+                    self.push(TokenKind::String, start, position);
+                }
+                // StringName, NodePath and raw string literals
+                '&' | '^' | '@' | 'r' if matches!(self.peek_nth(1), Some('"') | Some('\'')) => {
+                    self.bump();
+                    let quote = self.bump().unwrap();
+                    self.string(quote, c == 'r', position);
+                    self.push(TokenKind::String, start, position);
+                }
+                x if x.is_alphabetic() || x == '_' => {
+                    self.bump_while(|c| c.is_alphanumeric() || c == '_');
+                    self.push(TokenKind::Identifier, start, position);
+                }
+                x if x.is_ascii_digit()
+                    || (x == '.' && self.peek_nth(1).is_some_and(|n| n.is_ascii_digit())) =>
+                {
+                    self.number();
+                    self.push(TokenKind::Number, start, position);
+                }
+                _ => {
+                    let rest = &self.source[self.pos..];
+                    let len = OPERATORS
+                        .iter()
+                        .find(|op| rest.starts_with(*op))
+                        .map_or(1, |op| op.chars().count());
+                    for _ in 0..len {
+                        self.bump();
+                    }
+
+                    let span = self.span_from(position);
+                    match c {
+                        '(' | '[' | '{' if len == 1 => self.brackets.push((c, span)),
+                        ')' | ']' | '}' => self.close_bracket(c, span),
+                        _ => (),
+                    }
+                    self.push(TokenKind::Symbol, start, position);
+                }
+            }
+        }
+
+        if let Some((_, span)) = self.brackets.last() {
+            self.error(*span, "Unexpected eof, mismatched parentheses");
+        }
+
+        self.push(TokenKind::Newline, self.pos, (self.line, self.column));
+        self.tokens
+    }
+}
+
+/// Splits the source of a GDScript file into tokens. Malformed tokens are reported as
+/// diagnostics, lexing continues after them.
+pub fn tokenize<'a>(
+    filename: &'a str,
+    source: &'a str,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Vec<Token<'a>> {
+    let lexer = Lexer {
+        filename,
+        source,
+        pos: 0,
+        line: 1,
+        column: 1,
+        brackets: Vec::new(),
+        tokens: Vec::new(),
+        diagnostics,
+    };
+
+    lexer.tokenize()
+}
+
+/// Returns the source text of the tokens, with whitespace between them collapsed into single spaces.
+pub fn tokens_text(tokens: &[Token]) -> String {
+    let mut text = String::new();
+    let mut last_end = None;
+
+    for token in tokens.iter().filter(|t| t.kind != TokenKind::Comment) {
+        if last_end.is_some_and(|end| end < token.span.start) {
+            text.push(' ');
+        }
+        text += token.text;
+        last_end = Some(token.span.end);
+    }
+
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lex(source: &str) -> (Vec<(TokenKind, String)>, Vec<Diagnostic>) {
+        let mut diagnostics = Vec::new();
+        let tokens = tokenize("test.gd", source, &mut diagnostics)
+            .iter()
+            .map(|t| (t.kind, t.text.to_string()))
+            .collect();
+        (tokens, diagnostics)
+    }
+
+    fn texts(source: &str) -> Vec<String> {
+        lex(source).0.into_iter().map(|(_, text)| text).collect()
+    }
+
+    #[test]
+    fn strings() {
+        let (tokens, diagnostics) = lex(r#"a = "x" + 'y' + &"name" + ^"path" + r"\d""#);
+        assert!(diagnostics.is_empty());
+        let strings: Vec<_> = tokens
+            .iter()
+            .filter(|(kind, _)| *kind == TokenKind::String)
+            .map(|(_, text)| text.as_str())
+            .collect();
+        assert_eq!(
+            strings,
+            [r#""x""#, "'y'", r#"&"name""#, r#"^"path""#, r#"r"\d""#]
+        );
+
+        let (tokens, diagnostics) = lex("\"\"\"one\n\"two\"\n\"\"\"");
+        assert!(diagnostics.is_empty());
+        assert_eq!(
+            tokens[0],
+            (TokenKind::String, "\"\"\"one\n\"two\"\n\"\"\"".to_string())
+        );
+    }
+
+    #[test]
+    fn escapes() {
+        assert_eq!(texts(r#""a\"b" c"#), [r#""a\"b""#, "c", ""]);
+        assert_eq!(texts(r#"'\\' c"#), [r"'\\'", "c", ""]);
+
+        let (_, diagnostics) = lex("\"open\nx");
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].message, "unterminated string");
+    }
+
+    #[test]
+    fn newlines_in_brackets() {
+        let (tokens, diagnostics) = lex("f(a,\n  [b,\n  c])\nd");
+        assert!(diagnostics.is_empty());
+        let newlines = tokens
+            .iter()
+            .filter(|(kind, _)| *kind == TokenKind::Newline)
+            .count();
+        // One ending the call, one at the end of the file
+        assert_eq!(newlines, 2);
+
+        let (_, diagnostics) = lex("f(a,\n");
+        assert_eq!(
+            diagnostics[0].message,
+            "Unexpected eof, mismatched parentheses"
+        );
+        let (_, diagnostics) = lex("f(a]");
+        assert_eq!(diagnostics[0].notes.len(), 1);
+    }
+
+    #[test]
+    fn line_continuation() {
+        assert_eq!(
+            texts("a = 1 + \\\n  2\nb"),
+            ["a", "=", "1", "+", "2", "\n", "b", ""]
+        );
+        assert_eq!(texts("a \\\r\n b"), ["a", "b", ""]);
+
+        let (_, diagnostics) = lex("a \\\n");
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn comments() {
+        let (tokens, _) = lex("var a = 1 # trailing\n## doc \"not a string\"\n");
+        let comments: Vec<_> = tokens
+            .iter()
+            .filter(|(kind, _)| *kind == TokenKind::Comment)
+            .map(|(_, text)| text.as_str())
+            .collect();
+        assert_eq!(comments, ["# trailing", "## doc \"not a string\""]);
+        assert_eq!(texts("\"#\" # x"), ["\"#\"", "# x", ""]);
+    }
+
+    #[test]
+    fn numbers_and_operators() {
+        assert_eq!(
+            texts("1.5e-3 0xFF 1.abs() a **= b"),
+            ["1.5e-3", "0xFF", "1", ".", "abs", "(", ")", "a", "**=", "b", ""]
+        );
+    }
+
+    #[test]
+    fn text_of_tokens() {
+        let mut diagnostics = Vec::new();
+        let tokens = tokenize("test.gd", "f( a,b )  # x", &mut diagnostics);
+        // Without the newline ending the file
+        assert_eq!(tokens_text(&tokens[..tokens.len() - 1]), "f( a,b )");
+    }
+}
@@ -0,0 +1,409 @@
+use crate::coverage::is_documented;
+use crate::diagnostic::{Diagnostic, Severity};
+use crate::parser::{
+    DocumentationEntry, EntryType, FunctionArgument, InheritedMembers, Symbol, SymbolArgs,
+};
+use crate::project::ProjectFile;
+
+use serde::Deserialize;
+
+use std::collections::HashMap;
+
+#[derive(Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Level {
+    Allow,
+    Warn,
+    Deny,
+}
+
+// The lint rules along with their default level
+pub const RULES: &[(&str, Level)] = &[
+    ("undocumented_param", Level::Allow),
+    ("untyped_argument", Level::Allow),
+    ("untyped_return", Level::Allow),
+    ("unknown_setget", Level::Warn),
+    ("redundant_comment", Level::Warn),
+    ("duplicate_symbol", Level::Warn),
+    ("export_without_default", Level::Allow),
+];
+
+/// The levels of the lint rules, the defaults overridden by "lints" in the config file.
+pub struct Lints {
+    levels: HashMap<&'static str, Level>,
+}
+
+impl Lints {
+    pub fn new(config: HashMap<String, Level>) -> Result<Lints, String> {
+        let mut levels = RULES.iter().copied().collect::<HashMap<_, _>>();
+        for (rule, level) in config {
+            match RULES.iter().find(|(name, _)| *name == rule) {
+                Some((name, _)) => levels.insert(name, level),
+                None => return Err(format!("Unknown lint rule '{}'", rule)),
+            };
+        }
+
+        Ok(Lints { levels })
+    }
+
+    fn report(
+        &self,
+        rule: &str,
+        allowed: &[&str],
+        mut diagnostic: Diagnostic,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) {
+        diagnostic.severity = match self.levels[rule] {
+            _ if allowed.contains(&rule) => return,
+            Level::Allow => return,
+            Level::Warn => Severity::Warning,
+            Level::Deny => Severity::Error,
+        };
+        diagnostics.push(diagnostic.with_rule(rule));
+    }
+}
+
+/// Checks the doc comments and declarations of all scripts against the lint rules.
+pub fn lint(files: &[ProjectFile], lints: &Lints, diagnostics: &mut Vec<Diagnostic>) {
+    for file in files {
+        let path = file.path.strip_prefix(".").unwrap_or(&file.path);
+        let path = path.display().to_string();
+        let allowed = file
+            .data
+            .allowed_lints
+            .iter()
+            .map(String::as_str)
+            .collect::<Vec<_>>();
+
+        let mut found = Vec::new();
+        lint_entries(
+            &path,
+            &file.data.entries,
+            &file.data.inherited,
+            &file.data.declared_functions,
+            &allowed,
+            lints,
+            &mut found,
+        );
+
+        found.sort_by_key(|d| (d.span.line, d.span.column));
+        diagnostics.extend(found);
+    }
+}
+
+// Letters and digits of the text in lower case, "Get the_health." becomes "getthehealth"
+fn normalize(text: &str) -> String {
+    text.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+fn lint_entries(
+    filename: &str,
+    entries: &[DocumentationEntry],
+    inherited: &[InheritedMembers],
+    declared_functions: &[String],
+    allowed: &[&str],
+    lints: &Lints,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    // Setters and getters may be declared by the class or a project-defined ancestor
+    let functions = declared_functions
+        .iter()
+        .chain(
+            inherited
+                .iter()
+                .flat_map(|i| i.entries.iter())
+                .filter(|e| e.entry_type == EntryType::FUNC)
+                .flat_map(|e| e.names.iter()),
+        )
+        .collect::<Vec<_>>();
+
+    // Duplicates are reported at the later declaration
+    let mut symbols = entries
+        .iter()
+        .flat_map(|e| e.symbols.iter())
+        .collect::<Vec<_>>();
+    symbols.sort_by_key(|s| (s.span.line, s.span.column));
+
+    let mut declared: HashMap<&str, &Symbol> = HashMap::new();
+    for symbol in symbols {
+        let mut allowed = allowed.to_vec();
+        allowed.extend(symbol.allowed_lints.iter().map(String::as_str));
+        let allowed = allowed.as_slice();
+        let warning = |span, message| Diagnostic::warning(filename, span, message);
+
+        if let Some(first) = declared.insert(&symbol.name, symbol) {
+            lints.report(
+                "duplicate_symbol",
+                allowed,
+                warning(
+                    symbol.span,
+                    format!("'{}' is declared more than once", symbol.name),
+                )
+                .with_note(first.span, "first declared here"),
+                diagnostics,
+            );
+        }
+
+        let text = normalize(&symbol.text.join(" "));
+        if !text.is_empty() && text == normalize(&symbol.name) {
+            lints.report(
+                "redundant_comment",
+                allowed,
+                warning(
+                    symbol.span,
+                    format!("the comment of '{}' only repeats its name", symbol.name),
+                ),
+                diagnostics,
+            );
+        }
+
+        let public = !symbol.name.starts_with('_');
+        let (setter, getter) = match &symbol.arg {
+            Some(SymbolArgs::FunctionArgs(function)) => {
+                lint_arguments(
+                    filename,
+                    "function",
+                    symbol,
+                    &function.arguments,
+                    allowed,
+                    lints,
+                    diagnostics,
+                );
+
+                if public && function.return_type.is_none() {
+                    lints.report(
+                        "untyped_return",
+                        allowed,
+                        warning(
+                            symbol.span,
+                            format!("public function '{}' has no return type", symbol.name),
+                        ),
+                        diagnostics,
+                    );
+                }
+                (&None, &None)
+            }
+            Some(SymbolArgs::SignalArgs(arguments)) => {
+                lint_arguments(
+                    filename,
+                    "signal",
+                    symbol,
+                    arguments,
+                    allowed,
+                    lints,
+                    diagnostics,
+                );
+                (&None, &None)
+            }
+            Some(SymbolArgs::VariableArgs(variable)) => (&variable.setter, &variable.getter),
+            Some(SymbolArgs::ExportArgs(export)) => {
+                if export.assignment.is_none() {
+                    lints.report(
+                        "export_without_default",
+                        allowed,
+                        warning(
+                            symbol.span,
+                            format!("exported variable '{}' has no default value", symbol.name),
+                        ),
+                        diagnostics,
+                    );
+                }
+                (&export.setter, &export.getter)
+            }
+            Some(SymbolArgs::ClassArgs(class)) => {
+                lint_entries(
+                    filename,
+                    &class.entries,
+                    &class.inherited,
+                    &class.declared_functions,
+                    allowed,
+                    lints,
+                    diagnostics,
+                );
+                (&None, &None)
+            }
+            Some(SymbolArgs::EnumArgs(_)) | None => (&None, &None),
+        };
+
+        for (kind, function) in [("setter", setter), ("getter", getter)] {
+            match function {
+                // Accessors with an inline body, named like "@x_setter", are part of the property
+                Some(function) if !function.starts_with('@') && !functions.contains(&function) => {
+                    lints.report(
+                        "unknown_setget",
+                        allowed,
+                        warning(
+                            symbol.span,
+                            format!(
+                                "{} '{}' of '{}' is not a function of the class",
+                                kind, function, symbol.name
+                            ),
+                        ),
+                        diagnostics,
+                    )
+                }
+                _ => (),
+            }
+        }
+    }
+}
+
+fn lint_arguments(
+    filename: &str,
+    kind: &str,
+    symbol: &Symbol,
+    arguments: &[FunctionArgument],
+    allowed: &[&str],
+    lints: &Lints,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let public = !symbol.name.starts_with('_');
+    for argument in arguments {
+        if is_documented(symbol) && !symbol.tags.params.iter().any(|p| p.name == argument.name) {
+            lints.report(
+                "undocumented_param",
+                allowed,
+                Diagnostic::warning(
+                    filename,
+                    argument.span,
+                    format!(
+                        "argument '{}' of {} '{}' has no @param",
+                        argument.name, kind, symbol.name
+                    ),
+                ),
+                diagnostics,
+            );
+        }
+
+        if public && argument.value_type.is_none() {
+            lints.report(
+                "untyped_argument",
+                allowed,
+                Diagnostic::warning(
+                    filename,
+                    argument.span,
+                    format!(
+                        "argument '{}' of public {} '{}' has no type",
+                        argument.name, kind, symbol.name
+                    ),
+                ),
+                diagnostics,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::project_files;
+    use crate::project::resolve_inheritance;
+
+    fn lint_scripts(scripts: &[(&str, &str)], config: &[(&str, Level)]) -> Vec<Diagnostic> {
+        let mut files = project_files(scripts);
+        resolve_inheritance(&mut files);
+
+        let config = config
+            .iter()
+            .map(|(rule, level)| (rule.to_string(), *level))
+            .collect();
+        let mut diagnostics = Vec::new();
+        lint(&files, &Lints::new(config).unwrap(), &mut diagnostics);
+        diagnostics
+    }
+
+    fn rules(diagnostics: &[Diagnostic]) -> Vec<(&str, u32)> {
+        diagnostics
+            .iter()
+            .map(|d| (d.rule.as_deref().unwrap(), d.span.line))
+            .collect()
+    }
+
+    #[test]
+    fn default_rules() {
+        let source = "## Speed.\n\
+                      var speed\n\
+                      var health setget set_health\n\
+                      var speed\n\
+                      func f(a):\n\
+                      \tpass\n";
+        let diagnostics = lint_scripts(&[("a.gd", source)], &[]);
+        assert_eq!(
+            rules(&diagnostics),
+            [
+                ("redundant_comment", 2),
+                ("unknown_setget", 3),
+                ("duplicate_symbol", 4)
+            ]
+        );
+        assert!(diagnostics.iter().all(|d| d.severity == Severity::Warning));
+        assert_eq!(diagnostics[2].notes[0].span.line, 2);
+    }
+
+    #[test]
+    fn configured_levels() {
+        let source = "## Moves.\n\
+                      func move(speed, dir: int):\n\
+                      \tpass\n\
+                      @export var x: int\n";
+        let diagnostics = lint_scripts(
+            &[("a.gd", source)],
+            &[
+                ("undocumented_param", Level::Warn),
+                ("untyped_argument", Level::Deny),
+                ("untyped_return", Level::Warn),
+                ("export_without_default", Level::Warn),
+            ],
+        );
+        assert_eq!(
+            rules(&diagnostics),
+            [
+                ("untyped_return", 2),
+                ("undocumented_param", 2),
+                ("untyped_argument", 2),
+                ("undocumented_param", 2),
+                ("export_without_default", 4),
+            ]
+        );
+        assert_eq!(diagnostics[2].severity, Severity::Error);
+
+        let config = HashMap::from([("no_such_rule".to_string(), Level::Deny)]);
+        assert!(Lints::new(config).is_err());
+    }
+
+    #[test]
+    fn allow_comments() {
+        let source = "## Speed\n\
+                      # [Allow(redundant_comment)]\n\
+                      var speed\n\
+                      ## Jump\n\
+                      var jump\n";
+        assert_eq!(
+            rules(&lint_scripts(&[("a.gd", source)], &[])),
+            [("redundant_comment", 5)]
+        );
+
+        let source = format!("# [Allow(redundant_comment)]\nextends Node\n{}", source);
+        assert!(lint_scripts(&[("a.gd", &source)], &[]).is_empty());
+    }
+
+    #[test]
+    fn accessors_of_ancestors() {
+        let base = "class_name Base\nfunc set_health(value):\n\tpass\n";
+        let derived = "extends Base\n\
+                       var health: int: set = set_health, get = get_health\n\
+                       var mana:\n\
+                       \tset(value):\n\
+                       \t\tpass\n";
+        let diagnostics = lint_scripts(&[("base.gd", base), ("derived.gd", derived)], &[]);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].file, "derived.gd");
+        assert_eq!(
+            diagnostics[0].message,
+            "getter 'get_health' of 'health' is not a function of the class"
+        );
+    }
+}
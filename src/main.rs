@@ -4,32 +4,128 @@ extern crate glob;
 extern crate serde;
 extern crate serde_json;
 
+use crate::backend::godotxmlbackend::GodotXmlBackend;
+use crate::backend::htmlbackend::HtmlBackend;
+use crate::backend::jsonbackend::{read_documents, JsonBackend};
 use crate::backend::markdownbackend::MarkdownBackend;
-use crate::backend::Backend;
+use crate::backend::rstbackend::RstBackend;
+use crate::backend::{Backend, Page};
 
 use ansi_term::Colour::Red;
-use clap::{App, Arg};
+use clap::{App, AppSettings, Arg, ArgMatches, SubCommand};
 use serde::Deserialize;
 
 use glob::Pattern;
 
+use std::collections::HashMap;
 use std::ffi::OsStr;
 use std::fs::File;
-use std::path::Path;
+use std::io::IsTerminal;
+use std::io::Write;
 use std::path::PathBuf;
+use std::path::{Component, Path};
 
 use std::fmt::Display;
 
 mod backend;
+mod coverage;
+mod diagnostic;
+mod diff;
+mod lexer;
+mod lint;
+mod markup;
 mod parser;
+mod project;
 
+use crate::coverage::measure;
+use crate::diagnostic::{sarif_log, Diagnostic, Severity};
+use crate::diff::{diff, Bump};
+use crate::lint::{lint, Level, Lints};
 use crate::parser::parse_file;
+use crate::parser::{DocumentationData, SourceSpan};
+use crate::project::{
+    git_checkout, plugin_version, resolve_inheritance, resource_prefix, ProjectFile,
+};
 
-fn handle_error<T, R: Display>(x: Result<T, R>, message: &str) -> T {
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum DiagnosticsFormat {
+    Human,
+    Json,
+    Sarif,
+    Github,
+}
+
+// How errors and warnings are printed, as chosen on the command line
+#[derive(Clone, Copy)]
+struct Reporting {
+    // Whether stderr is coloured
+    colors: bool,
+    format: DiagnosticsFormat,
+}
+
+// Prints the diagnostics and the error, which aborted the run, if any.
+// Human readable diagnostics go to stderr along with the lines of the scripts they refer to,
+// the other formats go to stdout with the paths relative to the git repository.
+fn report(
+    reporting: Reporting,
+    diagnostics: &[Diagnostic],
+    fatal: Option<String>,
+    input_dir: &Path,
+    source_prefix: &str,
+) {
+    if reporting.format == DiagnosticsFormat::Human {
+        let mut sources = HashMap::new();
+        for diagnostic in diagnostics {
+            let source = sources
+                .entry(&diagnostic.file)
+                .or_insert_with(|| std::fs::read_to_string(input_dir.join(&diagnostic.file)).ok());
+            eprintln!("{}", diagnostic.render(source.as_deref(), reporting.colors));
+        }
+
+        if let Some(message) = fatal {
+            if reporting.colors {
+                eprintln!("{}", Red.paint(message));
+            } else {
+                eprintln!("{}", message);
+            }
+        }
+        return;
+    }
+
+    let mut diagnostics = diagnostics
+        .iter()
+        .cloned()
+        .map(|mut d| {
+            d.file = format!("{}{}", source_prefix, d.file);
+            d
+        })
+        .collect::<Vec<_>>();
+    if let Some(message) = fatal {
+        diagnostics.push(Diagnostic::error("", SourceSpan::default(), message));
+    }
+
+    match reporting.format {
+        DiagnosticsFormat::Json => {
+            for diagnostic in &diagnostics {
+                println!("{}", serde_json::to_string(diagnostic).unwrap());
+            }
+        }
+        DiagnosticsFormat::Sarif => println!("{}", sarif_log(&diagnostics)),
+        DiagnosticsFormat::Github => {
+            for diagnostic in &diagnostics {
+                println!("{}", diagnostic.github_annotation());
+            }
+        }
+        DiagnosticsFormat::Human => unreachable!(),
+    }
+}
+
+fn handle_error<T, R: Display>(reporting: Reporting, x: Result<T, R>, message: &str) -> T {
     match x {
         Ok(y) => y,
         Err(e) => {
-            eprintln!("{}", Red.paint(format!("{}: {}", message, e)));
+            let fatal = Some(format!("{}: {}", message, e));
+            report(reporting, &[], fatal, Path::new("."), "");
             ::std::process::exit(1);
         }
     }
@@ -40,6 +136,35 @@ struct Configuration {
     backend: Option<String>,
     excluded_files: Option<Vec<String>>,
     show_prefixed: Option<bool>,
+    source_url_template: Option<String>,
+    source_rev: Option<String>,
+    min_coverage: Option<f64>,
+    lints: Option<HashMap<String, Level>>,
+}
+
+fn read_config(reporting: Reporting, input_dir: &Path) -> Configuration {
+    match File::open(input_dir.join("godotdoc_config.json")) {
+        Ok(f) => handle_error(
+            reporting,
+            serde_json::from_reader(f),
+            "Error while reading config file",
+        ),
+        Err(_) => Configuration::default(),
+    }
+}
+
+fn compile_patterns(reporting: Reporting, patterns: Option<Vec<String>>) -> Vec<Pattern> {
+    patterns
+        .unwrap_or_default()
+        .iter()
+        .map(|s| {
+            handle_error(
+                reporting,
+                Pattern::new(s.as_str()).map_err(|e| e.to_string()),
+                "Couldn't parse pattern",
+            )
+        })
+        .collect()
 }
 
 pub struct Settings<'a> {
@@ -48,6 +173,11 @@ pub struct Settings<'a> {
 
     excluded_files: Vec<Pattern>,
     show_prefixed: bool,
+
+    // Template of the links to the declarations, with the revision filled in
+    source_url_template: Option<String>,
+    // Path of the input directory inside of the repository
+    source_prefix: String,
 }
 
 fn main() {
@@ -55,6 +185,7 @@ fn main() {
         .version("1.0")
         .author("Florian Kothmeier <floriankothmeier@web.de>")
         .about("Documentation generator for Gdscript")
+        .setting(AppSettings::SubcommandsNegateReqs)
         .arg(
             Arg::with_name("backend")
                 .help("Sets the type of file, which will be generated")
@@ -67,7 +198,7 @@ fn main() {
                 .short("o")
                 .long("output")
                 .value_name("Directory")
-                .required(true),
+                .required_unless("check"),
         )
         .arg(
             Arg::with_name("show_prefixed")
@@ -79,76 +210,354 @@ fn main() {
                 .help("Hide members prefixed with an '_'")
                 .long("hide_prefixed"),
         )
+        .arg(
+            Arg::with_name("color")
+                .help("Whether to colour errors and warnings")
+                .long("color")
+                .value_name("WHEN")
+                .possible_values(&["auto", "always", "never"])
+                .default_value("auto")
+                .global(true),
+        )
+        .arg(
+            Arg::with_name("diagnostics_format")
+                .help("Sets the format of errors and warnings, all but human are printed to stdout")
+                .long("diagnostics-format")
+                .value_name("FORMAT")
+                .possible_values(&["human", "json", "sarif", "github"])
+                .default_value("human")
+                .global(true),
+        )
+        .arg(
+            Arg::with_name("check")
+                .help("Reports the documentation coverage instead of generating files")
+                .long("check"),
+        )
+        .arg(
+            Arg::with_name("min_coverage")
+                .help("Fails the check if less than this percentage of the symbols is documented")
+                .long("min-coverage")
+                .value_name("PERCENT")
+                .requires("check"),
+        )
         .arg(Arg::with_name("input directory").required(true).index(1))
+        .subcommand(
+            SubCommand::with_name("diff")
+                .about("Reports the changes of the public API between two versions of a project")
+                .arg(
+                    Arg::with_name("format")
+                        .help("Sets the format of the report")
+                        .long("format")
+                        .possible_values(&["markdown", "json"])
+                        .default_value("markdown"),
+                )
+                .arg(
+                    Arg::with_name("output")
+                        .help("Writes the report to this file instead of stdout")
+                        .short("o")
+                        .long("output")
+                        .value_name("File"),
+                )
+                .arg(
+                    Arg::with_name("old")
+                        .help("Source directory or json backend output of the old version")
+                        .required(true)
+                        .index(1),
+                )
+                .arg(
+                    Arg::with_name("new")
+                        .help("Source directory or json backend output of the new version")
+                        .required(true)
+                        .index(2),
+                ),
+        )
         .get_matches();
 
+    let colors = match matches.value_of("color") {
+        Some("always") => true,
+        Some("never") => false,
+        _ => std::io::stderr().is_terminal() && std::env::var_os("NO_COLOR").is_none(),
+    };
+    let format = match matches.value_of("diagnostics_format") {
+        Some("json") => DiagnosticsFormat::Json,
+        Some("sarif") => DiagnosticsFormat::Sarif,
+        Some("github") => DiagnosticsFormat::Github,
+        _ => DiagnosticsFormat::Human,
+    };
+    let reporting = Reporting { colors, format };
+
+    if let Some(matches) = matches.subcommand_matches("diff") {
+        diff_command(reporting, matches);
+        return;
+    }
+
     let input_dir = matches.value_of("input directory").unwrap();
-    let output_dir = matches.value_of("output").unwrap();
+    let output_dir = matches.value_of("output").unwrap_or_default();
     let show_prefixed = matches
         .value_of("show_prefixed")
         .map(|_| true)
         .or(matches.value_of("hide_prefixed").map(|_| false));
-    let config;
-    if let Ok(f) = File::open(Path::new(input_dir).join("godotdoc_config.json")) {
-        config = handle_error(
-            serde_json::from_reader(f),
-            "Error while reading config file",
-        );
-    } else {
-        config = Configuration::default();
-    }
+    let config = read_config(reporting, Path::new(input_dir));
 
-    let config_backend = config.backend.as_ref().map(|s| s.as_str());
+    let config_backend = config.backend.as_deref();
     let backend: Box<dyn Backend> = handle_error(
+        reporting,
         get_backend(matches.value_of("backend").or(config_backend)),
         "Error",
     );
 
+    let lints = handle_error(
+        reporting,
+        Lints::new(config.lints.unwrap_or_default()),
+        "Error while reading config file",
+    );
+    let min_coverage = match matches.value_of("min_coverage") {
+        Some(x) => Some(handle_error(
+            reporting,
+            x.parse::<f64>(),
+            "Invalid minimum coverage",
+        )),
+        None => config.min_coverage,
+    };
+
+    let checkout = git_checkout(Path::new(input_dir));
+    let source_rev = config.source_rev;
+    let source_url_template = config.source_url_template.map(|template| {
+        if !template.contains("{rev}") {
+            return template;
+        }
+
+        let rev = source_rev.or_else(|| checkout.as_ref().and_then(|(_, rev)| rev.clone()));
+        let rev = handle_error(
+            reporting,
+            rev.ok_or("the input directory is not part of a git checkout, set source_rev instead"),
+            "Couldn't determine the revision for source_url_template",
+        );
+        template.replace("{rev}", &rev)
+    });
+
     let settings = Settings {
-        backend: backend,
+        backend,
         output_path: Path::new(output_dir),
 
-        excluded_files: config
-            .excluded_files
-            .unwrap_or(Vec::new())
-            .drain(..)
-            .map(|s| {
-                handle_error(
-                    Pattern::new(s.as_str()).map_err(|e| e.to_string()),
-                    "Couldn't parse pattern",
-                )
-            })
-            .collect(),
+        excluded_files: compile_patterns(reporting, config.excluded_files),
         show_prefixed: show_prefixed.or(config.show_prefixed).unwrap_or(true),
+
+        source_url_template,
+        source_prefix: checkout.map(|(prefix, _)| prefix).unwrap_or_default(),
     };
-    handle_error(
-        traverse_directory(
-            Path::new(input_dir).to_path_buf(),
-            Path::new(".").to_path_buf(),
-            &settings,
-        ),
-        "Error",
+    let mut files = Vec::new();
+    let mut diagnostics = Vec::new();
+    let mut coverage = None;
+    let result = traverse_directory(
+        Path::new(input_dir).to_path_buf(),
+        Path::new(".").to_path_buf(),
+        &resource_prefix(Path::new(input_dir)),
+        &settings,
+        &mut files,
+        &mut diagnostics,
     )
+    .and_then(|_| {
+        // Scripts with errors are documented as far as they could be parsed
+        resolve_inheritance(&mut files);
+        lint(&files, &lints, &mut diagnostics);
+        if !matches.is_present("check") {
+            return generate_files(files, &settings);
+        }
+
+        let total = coverage.insert(measure(&files, &mut diagnostics)).total;
+        match min_coverage {
+            Some(min) if total.percentage() < min => Err(format!(
+                "Documentation coverage of {:.1}% is below the minimum of {}%",
+                total.percentage(),
+                min
+            )),
+            _ => Ok(()),
+        }
+    });
+
+    // Reported together, so the machine readable formats stay a single document
+    let fatal = result.err().map(|e| format!("Error: {}", e));
+    let failed = fatal.is_some() || diagnostics.iter().any(|d| d.severity == Severity::Error);
+    report(
+        reporting,
+        &diagnostics,
+        fatal,
+        Path::new(input_dir),
+        &settings.source_prefix,
+    );
+    // The report goes to stderr if stdout is taken by the diagnostics
+    if let Some(coverage) = coverage {
+        if reporting.format == DiagnosticsFormat::Human {
+            print!("{}", coverage);
+        } else {
+            eprint!("{}", coverage);
+        }
+    }
+
+    if failed {
+        ::std::process::exit(1);
+    }
+}
+
+// Joins the components of a path with '/', regardless of the platform
+fn slash_path(path: &Path) -> String {
+    path.components()
+        .filter(|c| c != &Component::CurDir)
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+// Reads the scripts of one version for the diff, a directory without any scripts is read as the
+// output of the json backend
+fn load_version(
+    reporting: Reporting,
+    dir: &Path,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Result<Vec<(String, DocumentationData)>, String> {
+    let config = read_config(reporting, dir);
+    let settings = Settings {
+        backend: Box::new(JsonBackend::new()),
+        output_path: Path::new(""),
+        excluded_files: compile_patterns(reporting, config.excluded_files),
+        show_prefixed: true,
+        source_url_template: None,
+        source_prefix: String::new(),
+    };
+
+    let mut files = Vec::new();
+    let mut found = Vec::new();
+    traverse_directory(
+        dir.to_path_buf(),
+        Path::new(".").to_path_buf(),
+        &resource_prefix(dir),
+        &settings,
+        &mut files,
+        &mut found,
+    )?;
+    // The diagnostics of both versions are reported together
+    for mut diagnostic in found {
+        diagnostic.file = dir.join(&diagnostic.file).display().to_string();
+        diagnostics.push(diagnostic);
+    }
+
+    let mut scripts = files
+        .into_iter()
+        .map(|f| (slash_path(&f.path), f.data))
+        .collect::<Vec<_>>();
+    if scripts.is_empty() {
+        let mut documents = Vec::new();
+        read_documents(dir, &mut documents)?;
+        scripts = documents
+            .into_iter()
+            .map(|(path, data)| (slash_path(&path), data))
+            .collect();
+    }
+    if scripts.is_empty() {
+        return Err(format!(
+            "Found neither scripts nor JSON documents in {}",
+            dir.display()
+        ));
+    }
+
+    // Sorted, so the report doesn't depend on the order of the directory entries
+    scripts.sort_by(|(a, _), (b, _)| a.cmp(b));
+    Ok(scripts)
+}
+
+fn diff_command(reporting: Reporting, matches: &ArgMatches) {
+    let old_dir = Path::new(matches.value_of("old").unwrap());
+    let new_dir = Path::new(matches.value_of("new").unwrap());
+    let mut diagnostics = Vec::new();
+    let result = load_version(reporting, old_dir, &mut diagnostics).and_then(|old| {
+        let new = load_version(reporting, new_dir, &mut diagnostics)?;
+        let mut api_diff = diff(&old, &new);
+        let versions = (plugin_version(old_dir)?, plugin_version(new_dir)?);
+        if let (Some(old), Some(new)) = versions {
+            api_diff.old_version = Some(old.to_string());
+            api_diff.new_version = Some(new.to_string());
+        }
+
+        let mut output: Box<dyn Write> = match matches.value_of("output") {
+            Some(path) => Box::new(
+                File::create(path)
+                    .map_err(|e| format!("Failed to open output file: {}, {}", path, e))?,
+            ),
+            None => Box::new(std::io::stdout()),
+        };
+        match matches.value_of("format") {
+            Some("json") => serde_json::to_writer_pretty(&mut output, &api_diff)
+                .map_err(|e| e.to_string())
+                .and_then(|_| writeln!(output).map_err(|e| e.to_string())),
+            _ => api_diff
+                .write_markdown(&mut output)
+                .map_err(|e| e.to_string()),
+        }?;
+
+        match versions {
+            (Some(old), Some(new))
+                if api_diff.bump == Bump::Major && Bump::between(&old, &new) < Bump::Major =>
+            {
+                Err(format!(
+                    "The public API changed incompatibly, but the version in plugin.cfg only went from {} to {}",
+                    old, new
+                ))
+            }
+            (old, new) if api_diff.bump == Bump::Major => {
+                // Without both versions, the bump can't be checked
+                for (dir, version) in [(old_dir, old), (new_dir, new)] {
+                    if version.is_none() {
+                        diagnostics.push(Diagnostic::warning(
+                            &dir.display().to_string(),
+                            SourceSpan::default(),
+                            "The public API changed incompatibly, but the version can't be checked: there is no single plugin.cfg with a version".to_string(),
+                        ));
+                    }
+                }
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    });
+
+    let fatal = result.err().map(|e| format!("Error: {}", e));
+    let failed = fatal.is_some() || diagnostics.iter().any(|d| d.severity == Severity::Error);
+    report(reporting, &diagnostics, fatal, Path::new(""), "");
+    if failed {
+        ::std::process::exit(1);
+    }
 }
 
 fn get_backend(name: Option<&str>) -> Result<Box<dyn Backend>, String> {
     match name {
         Some("markdown") | None => Ok(Box::new(MarkdownBackend::new())),
+        Some("html") => Ok(Box::new(HtmlBackend::new())),
+        Some("json") => Ok(Box::new(JsonBackend::new())),
+        Some("godot-xml") => Ok(Box::new(GodotXmlBackend::new())),
+        Some("rst") => Ok(Box::new(RstBackend::new())),
         _ => Err("Unsupported backend".to_string()),
     }
 }
 
-fn path_matches_any(path: &Path, patterns: &Vec<Pattern>) -> bool {
+fn path_matches_any(path: &Path, patterns: &[Pattern]) -> bool {
     for pattern in patterns {
         if pattern.matches_path(path) {
             return true;
         }
     }
 
-    return false;
+    false
 }
 
-fn traverse_directory(src: PathBuf, output: PathBuf, settings: &Settings) -> Result<(), String> {
+fn traverse_directory(
+    src: PathBuf,
+    output: PathBuf,
+    resource_prefix: &str,
+    settings: &Settings,
+    files: &mut Vec<ProjectFile>,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Result<(), String> {
     for entry in std::fs::read_dir(src).map_err(|e| e.to_string())? {
         let entry = entry.map_err(|e| e.to_string())?;
         let path = entry.path();
@@ -161,32 +570,65 @@ fn traverse_directory(src: PathBuf, output: PathBuf, settings: &Settings) -> Res
         }
 
         if path.is_dir() {
-            traverse_directory(path, new_output, settings)?;
+            traverse_directory(
+                path,
+                new_output,
+                resource_prefix,
+                settings,
+                files,
+                diagnostics,
+            )?;
         } else if path.is_file() && path.extension() == Some(OsStr::new("gd")) {
             let input = File::open(&path)
                 .map_err(|e| format!("Failed to open input file: {}, {}", path.display(), e))?;
-            let output_path = settings.output_path.join(&output).join(format!(
-                "{}.{}",
-                file_name.unwrap(),
-                settings.backend.get_extension()
-            ));
-
-            std::fs::create_dir_all(&output_path.parent().unwrap()).map_err(|e| e.to_string())?;
-            let mut output = File::create(&output_path).map_err(|e| {
-                format!(
-                    "Failed to open output file: {}, {}",
-                    output_path.display(),
-                    e
-                )
-            })?;
-            settings
-                .backend
-                .generate_output(
-                    parse_file(file_name.unwrap(), input, settings)?,
-                    &mut output,
-                )
-                .map_err(|e| e.to_string())?;
+
+            let relative_path = new_output.strip_prefix(".").unwrap_or(&new_output);
+            let data = parse_file(relative_path, input, settings, diagnostics)?;
+
+            files.push(ProjectFile::new(new_output, resource_prefix, data));
         }
     }
     Ok(())
 }
+
+fn generate_files(files: Vec<ProjectFile>, settings: &Settings) -> Result<(), String> {
+    let extension = settings.backend.get_extension();
+    let pages = files
+        .iter()
+        .map(|f| {
+            let mut page = Page::new(&f.path, f.resource_path.clone(), f.data.class_name.clone());
+            page.source_url = settings.source_url_template.as_ref().map(|template| {
+                let path = page
+                    .path
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                template.replace("{path}", &format!("{}{}", settings.source_prefix, path))
+            });
+            page
+        })
+        .collect::<Vec<_>>();
+
+    for (file, page) in files.into_iter().zip(&pages) {
+        let output_path = settings.output_path.join(page.output_path(&extension));
+
+        std::fs::create_dir_all(output_path.parent().unwrap()).map_err(|e| e.to_string())?;
+        let mut output = File::create(&output_path).map_err(|e| {
+            format!(
+                "Failed to open output file: {}, {}",
+                output_path.display(),
+                e
+            )
+        })?;
+        settings
+            .backend
+            .generate_output(file.data, page, &pages, settings.output_path, &mut output)
+            .map_err(|e| e.to_string())?;
+    }
+
+    settings
+        .backend
+        .generate_index(&pages, settings.output_path)
+        .map_err(|e| e.to_string())
+}
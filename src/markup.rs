@@ -0,0 +1,260 @@
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ReferenceKind {
+    Class,
+    Method,
+    Member,
+    Signal,
+    Constant,
+    Enum,
+    Annotation,
+    Param,
+}
+
+#[derive(PartialEq, Debug)]
+pub enum Markup {
+    Text(String),
+    LineBreak,
+    Bold(Vec<Markup>),
+    Italic(Vec<Markup>),
+    Code(String),
+    CodeBlock(String),
+    Reference(ReferenceKind, String),
+    Link(String, Vec<Markup>),
+}
+
+struct MarkupParser<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+fn is_class_name(s: &str) -> bool {
+    s.starts_with(|c: char| c.is_ascii_uppercase())
+        && s.chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '.')
+}
+
+// Removes the indentation common to all lines of a code block
+fn dedent(s: &str) -> String {
+    let s = s.trim_matches('\n');
+    let indent = s
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| l.len() - l.trim_start().len())
+        .min()
+        .unwrap_or(0);
+
+    s.lines()
+        .map(|l| if l.len() >= indent { &l[indent..] } else { "" })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+impl<'a> MarkupParser<'a> {
+    // Returns the text up to the closing tag and skips past it
+    fn raw_until(&mut self, closing: &str) -> &'a str {
+        let rest = &self.text[self.pos..];
+        match rest.find(closing) {
+            Some(end) => {
+                self.pos += end + closing.len();
+                &rest[..end]
+            }
+            None => {
+                self.pos = self.text.len();
+                rest
+            }
+        }
+    }
+
+    fn parse_tag(&mut self, tag: &str) -> Option<Markup> {
+        let (name, argument) = match tag.find([' ', '=']) {
+            Some(pos) => (&tag[..pos], Some(tag[pos + 1..].trim())),
+            None => (tag, None),
+        };
+        // Tags without a closing tag are kept as text, like the index in "arr[i]"
+        let closing = format!("[/{}]", name);
+        let closed = self.text[self.pos..].contains(&closing);
+
+        let node = match (name, argument) {
+            ("b", None) if closed => Markup::Bold(self.parse_nodes(Some("/b"))),
+            ("i", None) if closed => Markup::Italic(self.parse_nodes(Some("/i"))),
+            ("code", None) | ("kbd", None) if closed => {
+                Markup::Code(self.raw_until(&closing).to_string())
+            }
+            ("codeblock", _) if closed => Markup::CodeBlock(dedent(self.raw_until(&closing))),
+            ("url", None) if closed => {
+                let url = self.raw_until(&closing).to_string();
+                Markup::Link(url.clone(), vec![Markup::Text(url)])
+            }
+            ("url", Some(url)) if closed => {
+                Markup::Link(url.to_string(), self.parse_nodes(Some("/url")))
+            }
+            ("br", None) => Markup::LineBreak,
+            ("lb", None) => Markup::Text("[".to_string()),
+            ("rb", None) => Markup::Text("]".to_string()),
+            ("method", Some(x)) => Markup::Reference(ReferenceKind::Method, x.to_string()),
+            ("member", Some(x)) => Markup::Reference(ReferenceKind::Member, x.to_string()),
+            ("signal", Some(x)) => Markup::Reference(ReferenceKind::Signal, x.to_string()),
+            ("constant", Some(x)) => Markup::Reference(ReferenceKind::Constant, x.to_string()),
+            ("enum", Some(x)) => Markup::Reference(ReferenceKind::Enum, x.to_string()),
+            ("annotation", Some(x)) => Markup::Reference(ReferenceKind::Annotation, x.to_string()),
+            ("param", Some(x)) => Markup::Reference(ReferenceKind::Param, x.to_string()),
+            (x, None) if is_class_name(x) => Markup::Reference(ReferenceKind::Class, x.to_string()),
+            _ => return None,
+        };
+
+        Some(node)
+    }
+
+    fn parse_nodes(&mut self, closing: Option<&str>) -> Vec<Markup> {
+        let mut nodes = Vec::new();
+        let mut text = String::new();
+
+        while let Some(c) = self.text[self.pos..].chars().next() {
+            if c == '\n' {
+                if !text.is_empty() {
+                    nodes.push(Markup::Text(std::mem::take(&mut text)));
+                }
+                nodes.push(Markup::LineBreak);
+                self.pos += 1;
+                continue;
+            }
+
+            if c == '[' {
+                let rest = &self.text[self.pos + 1..];
+                if let Some(end) = rest.find(']') {
+                    let tag = &rest[..end];
+                    let start = self.pos;
+                    self.pos += end + 2;
+
+                    if Some(tag) == closing {
+                        break;
+                    }
+                    if let Some(node) = self.parse_tag(tag) {
+                        if !text.is_empty() {
+                            nodes.push(Markup::Text(std::mem::take(&mut text)));
+                        }
+                        nodes.push(node);
+                        continue;
+                    }
+                    self.pos = start;
+                }
+            }
+
+            text.push(c);
+            self.pos += c.len_utf8();
+        }
+
+        if !text.is_empty() {
+            nodes.push(Markup::Text(text));
+        }
+        nodes
+    }
+}
+
+/// Parses the lines of a comment, only '##' doc comments may contain Godot's BBCode markup.
+pub fn parse_markup(lines: &[String], doc_comment: bool) -> Vec<Markup> {
+    if !doc_comment {
+        let mut nodes = Vec::new();
+        for (i, line) in lines.iter().enumerate() {
+            if i > 0 {
+                nodes.push(Markup::LineBreak);
+            }
+            nodes.push(Markup::Text(line.clone()));
+        }
+        return nodes;
+    }
+
+    let text = lines.join("\n");
+    let mut parser = MarkupParser {
+        text: &text,
+        pos: 0,
+    };
+
+    parser.parse_nodes(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> Vec<Markup> {
+        parse_markup(&[text.to_string()], true)
+    }
+
+    fn text(s: &str) -> Markup {
+        Markup::Text(s.to_string())
+    }
+
+    #[test]
+    fn formatting_tags() {
+        assert_eq!(
+            parse("a [b]bold[/b] and [i]italic[/i]"),
+            vec![
+                text("a "),
+                Markup::Bold(vec![text("bold")]),
+                text(" and "),
+                Markup::Italic(vec![text("italic")]),
+            ]
+        );
+        assert_eq!(
+            parse("[code]a[i][/code][br][lb]x[rb]"),
+            vec![
+                Markup::Code("a[i]".to_string()),
+                Markup::LineBreak,
+                text("["),
+                text("x"),
+                text("]"),
+            ]
+        );
+    }
+
+    #[test]
+    fn code_blocks_are_dedented() {
+        assert_eq!(
+            parse("[codeblock]\n    if x:\n        pass\n[/codeblock]"),
+            vec![Markup::CodeBlock("if x:\n    pass".to_string())]
+        );
+    }
+
+    #[test]
+    fn references_and_links() {
+        assert_eq!(
+            parse("[method foo] [member Inner.bar] [Node] [url]https://a.b[/url]"),
+            vec![
+                Markup::Reference(ReferenceKind::Method, "foo".to_string()),
+                text(" "),
+                Markup::Reference(ReferenceKind::Member, "Inner.bar".to_string()),
+                text(" "),
+                Markup::Reference(ReferenceKind::Class, "Node".to_string()),
+                text(" "),
+                Markup::Link("https://a.b".to_string(), vec![text("https://a.b")]),
+            ]
+        );
+        assert_eq!(
+            parse("[url=https://a.b]the [b]site[/b][/url]"),
+            vec![Markup::Link(
+                "https://a.b".to_string(),
+                vec![text("the "), Markup::Bold(vec![text("site")])]
+            )]
+        );
+    }
+
+    #[test]
+    fn unclosed_and_unknown_tags_are_text() {
+        assert_eq!(
+            parse("see arr[i] for details"),
+            vec![text("see arr[i] for details")]
+        );
+        assert_eq!(parse("[b]open [code]x"), vec![text("[b]open [code]x")]);
+        assert_eq!(parse("a [unknown] tag"), vec![text("a [unknown] tag")]);
+    }
+
+    #[test]
+    fn plain_comments_are_not_parsed() {
+        let lines = vec!["[b]a[/b]".to_string(), "b".to_string()];
+        assert_eq!(
+            parse_markup(&lines, false),
+            vec![text("[b]a[/b]"), Markup::LineBreak, text("b")]
+        );
+    }
+}
@@ -1,13 +1,19 @@
 use std::fs::File;
-use std::io::BufRead;
-use std::io::BufReader;
-use std::io::Lines;
 use std::io::Read;
+use std::path::Path;
 
 use std::fmt::{Display, Formatter};
 
+use crate::diagnostic::Diagnostic;
+use crate::lexer::{tokenize, tokens_text, Token, TokenKind};
 use crate::Settings;
 
+use serde::{Deserialize, Serialize};
+
+// The variants are named after the keywords declaring the entries
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum EntryType {
     CLASS,
     SIGNAL,
@@ -32,136 +38,653 @@ impl Display for EntryType {
     }
 }
 
+impl EntryType {
+    pub fn singular(&self) -> &'static str {
+        match self {
+            EntryType::CLASS => "class",
+            EntryType::SIGNAL => "signal",
+            EntryType::FUNC => "function",
+            EntryType::VAR => "variable",
+            EntryType::CONST => "constant",
+            EntryType::EXPORT => "export",
+            EntryType::ENUM => "enum",
+        }
+    }
+}
+
+// Where a declaration is located in its file, lines and columns start at 1 and the end is exclusive
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub struct SourceSpan {
+    pub line: u32,
+    pub column: u32,
+    pub end_line: u32,
+    pub end_column: u32,
+}
+
+impl SourceSpan {
+    fn from_tokens(tokens: &[Token]) -> SourceSpan {
+        match (tokens.first(), tokens.last()) {
+            (Some(first), Some(last)) => SourceSpan {
+                line: first.line,
+                column: first.column,
+                end_line: last.end_line,
+                end_column: last.end_column,
+            },
+            _ => SourceSpan::default(),
+        }
+    }
+}
+
+impl Display for SourceSpan {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
 pub struct FunctionArgument {
     pub name: String,
     pub value_type: Option<String>,
     pub default_value: Option<String>,
+    pub span: SourceSpan,
 }
 
 impl Display for FunctionArgument {
     fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
         write!(f, "{}", self.name)?;
-        if self.value_type.is_some() {
-            write!(f, ": {}", self.value_type.as_ref().unwrap())?;
+        match (&self.value_type, &self.default_value) {
+            // Inferred type, "name := default"
+            (Some(value_type), Some(default_value)) if value_type.is_empty() => {
+                write!(f, " := {}", default_value)?
+            }
+            (value_type, default_value) => {
+                if let Some(value_type) = value_type {
+                    write!(f, ": {}", value_type)?;
+                }
+                if let Some(default_value) = default_value {
+                    write!(f, " = {}", default_value)?;
+                }
+            }
         }
-        if self.default_value.is_some() {
-            write!(f, " = {}", self.default_value.as_ref().unwrap())?;
+
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Annotation {
+    pub name: String,
+    pub arguments: Vec<String>,
+}
+
+impl Annotation {
+    pub fn is_export(&self) -> bool {
+        self.name == "export" || self.name.starts_with("export_")
+    }
+}
+
+impl Display for Annotation {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "@{}", self.name)?;
+        if !self.arguments.is_empty() {
+            write!(f, "({})", self.arguments.join(", "))?;
         }
 
         Ok(())
     }
 }
 
+#[derive(Serialize, Deserialize)]
 pub struct FunctionArgStruct {
     pub arguments: Vec<FunctionArgument>,
     pub super_arguments: Option<Vec<FunctionArgument>>,
     pub return_type: Option<String>,
+    pub overrides: Option<String>,
+    pub engine_virtual: bool,
 }
 
+#[derive(Serialize, Deserialize)]
 pub struct VariableArgStruct {
     pub value_type: Option<String>,
     pub assignment: Option<String>,
+    pub annotations: Vec<Annotation>,
     pub setter: Option<String>,
     pub getter: Option<String>,
 }
 
+/// How the inspector edits an exported property, from the options of a Godot 3 export or the
+/// Godot 4 annotation like `@export_range`.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "details", rename_all = "snake_case")]
+pub enum ExportHint {
+    Range {
+        min: String,
+        max: String,
+        step: Option<String>,
+        // Further hints like "or_greater" or "suffix:px"
+        options: Vec<String>,
+    },
+    ExpEasing(Vec<String>),
+    Enum(Vec<String>),
+    Flags(Vec<String>),
+    // The layers of the project settings, like "2d_physics"
+    Layers(String),
+    File {
+        filters: Vec<String>,
+        global: bool,
+    },
+    Dir {
+        global: bool,
+    },
+    Multiline,
+    Placeholder(String),
+    ColorNoAlpha,
+    NodePath(Vec<String>),
+    // Options of a Godot 3 export, which aren't recognized
+    Other(Vec<String>),
+}
+
+impl Display for ExportHint {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            ExportHint::Range {
+                min,
+                max,
+                step,
+                options,
+            } => {
+                write!(f, "Range {} to {}", min, max)?;
+                if let Some(step) = step {
+                    write!(f, ", step {}", step)?;
+                }
+                for option in options {
+                    match option.split_once(':') {
+                        Some(("suffix", suffix)) => write!(f, ", in {}", suffix)?,
+                        _ if option == "exp" => write!(f, ", exponential")?,
+                        _ => write!(f, ", {}", option.replace('_', " "))?,
+                    }
+                }
+                Ok(())
+            }
+            ExportHint::ExpEasing(options) => {
+                write!(f, "Easing curve")?;
+                for option in options {
+                    write!(f, ", {}", option.replace('_', " "))?;
+                }
+                Ok(())
+            }
+            ExportHint::Enum(choices) => write!(f, "One of {}", choices.join(", ")),
+            ExportHint::Flags(flags) if flags.is_empty() => write!(f, "Flags"),
+            ExportHint::Flags(flags) => write!(f, "Flags {}", flags.join(", ")),
+            ExportHint::Layers(layers) => {
+                // "2d_physics" becomes "2D physics layers"
+                for word in layers.split('_') {
+                    match word.strip_suffix('d') {
+                        Some(n) if n.chars().all(|c| c.is_ascii_digit()) => write!(f, "{}D ", n)?,
+                        _ => write!(f, "{} ", word)?,
+                    }
+                }
+                write!(f, "layers")
+            }
+            ExportHint::File { filters, global } => {
+                write!(f, "{}", if *global { "Global file" } else { "File" })?;
+                if !filters.is_empty() {
+                    write!(f, " ({})", filters.join(", "))?;
+                }
+                Ok(())
+            }
+            ExportHint::Dir { global: true } => write!(f, "Global directory"),
+            ExportHint::Dir { global: false } => write!(f, "Directory"),
+            ExportHint::Multiline => write!(f, "Multiline text"),
+            ExportHint::Placeholder(text) => write!(f, "Placeholder \"{}\"", text),
+            ExportHint::ColorNoAlpha => write!(f, "Color without alpha"),
+            ExportHint::NodePath(types) if types.is_empty() => write!(f, "Node path"),
+            ExportHint::NodePath(types) => write!(f, "Node path to {}", types.join(", ")),
+            ExportHint::Other(options) => write!(f, "{}", options.join(", ")),
+        }
+    }
+}
+
+// A group of exports, which the inspector shows without the prefix of their names
+#[derive(Clone, Default, PartialEq, Debug, Serialize, Deserialize)]
+pub struct ExportGroup {
+    pub name: String,
+    pub prefix: String,
+}
+
+/// Where the inspector shows an export, as set by `@export_category`, `@export_group` and
+/// `@export_subgroup` in front of it.
+#[derive(Clone, Default, PartialEq, Debug, Serialize, Deserialize)]
+pub struct InspectorSection {
+    pub category: Option<String>,
+    pub group: Option<ExportGroup>,
+    pub subgroup: Option<ExportGroup>,
+}
+
+impl InspectorSection {
+    /// Names of the nested sections along with their depth, the category is at depth 0.
+    pub fn levels(&self) -> Vec<(usize, &str)> {
+        [
+            self.category.as_deref(),
+            self.group.as_ref().map(|g| g.name.as_str()),
+            self.subgroup.as_ref().map(|g| g.name.as_str()),
+        ]
+        .iter()
+        .enumerate()
+        .filter_map(|(depth, name)| name.map(|name| (depth, name)))
+        .collect()
+    }
+
+    /// The headings to write in front of an export of this section, given the section of the
+    /// export before it. When a group ends, the heading of the enclosing section is repeated.
+    pub fn headings(&self, previous: &InspectorSection) -> Vec<(usize, &str)> {
+        let levels = self.levels();
+        let previous = previous.levels();
+        let common = levels
+            .iter()
+            .zip(&previous)
+            .take_while(|(a, b)| a == b)
+            .count();
+
+        match levels.len() {
+            _ if levels == previous => Vec::new(),
+            len if common == len => levels[len.saturating_sub(1)..].to_vec(),
+            _ => levels[common..].to_vec(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
 pub struct ExportArgStruct {
     pub value_type: Option<String>,
     pub assignment: Option<String>,
-    pub options: Vec<String>,
+    pub hint: Option<ExportHint>,
+    pub annotations: Vec<Annotation>,
     pub setter: Option<String>,
     pub getter: Option<String>,
+    #[serde(default)]
+    pub section: InspectorSection,
 }
 
+#[derive(Serialize, Deserialize)]
 pub struct EnumValue {
     pub name: String,
     pub value: isize,
     pub text: Vec<String>,
+    #[serde(default)]
+    pub doc_comment: bool,
+    pub span: SourceSpan,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ClassArgStruct {
+    pub extends: Option<String>,
+    pub entries: Vec<DocumentationEntry>,
+    // All functions of the class, including the hidden ones
+    #[serde(skip)]
+    pub declared_functions: Vec<String>,
+    pub inherited: Vec<InheritedMembers>,
 }
 
+#[allow(clippy::enum_variant_names, clippy::large_enum_variant)]
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "kind", content = "details")]
 pub enum SymbolArgs {
+    #[serde(rename = "function")]
     FunctionArgs(FunctionArgStruct),
+    #[serde(rename = "variable")]
     VariableArgs(VariableArgStruct),
+    #[serde(rename = "export")]
     ExportArgs(ExportArgStruct),
+    #[serde(rename = "enum")]
     EnumArgs(Vec<EnumValue>),
-    ClassArgs(Vec<DocumentationEntry>),
+    #[serde(rename = "signal")]
+    SignalArgs(Vec<FunctionArgument>),
+    #[serde(rename = "class")]
+    ClassArgs(ClassArgStruct),
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ParamDoc {
+    pub name: String,
+    pub text: String,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct DocTags {
+    pub params: Vec<ParamDoc>,
+    pub returns: Option<String>,
+    pub deprecated: Option<String>,
+    pub since: Option<String>,
+    pub experimental: Option<String>,
+}
+
+impl DocTags {
+    pub fn is_empty(&self) -> bool {
+        self.params.is_empty()
+            && self.returns.is_none()
+            && self.deprecated.is_none()
+            && self.since.is_none()
+            && self.experimental.is_none()
+    }
 }
 
+#[derive(Serialize, Deserialize)]
 pub struct Symbol {
     pub name: String,
     pub arg: Option<SymbolArgs>,
     pub text: Vec<String>,
+    // Whether the text is a '##' doc comment, which may contain BBCode markup
+    #[serde(default)]
+    pub doc_comment: bool,
+    pub tags: DocTags,
+    pub span: SourceSpan,
+    // Lint rules disabled by "[Allow(rule)]" in the comment
+    #[serde(skip)]
+    pub allowed_lints: Vec<String>,
 }
 
+#[derive(Serialize, Deserialize)]
 pub struct DocumentationEntry {
     pub entry_type: EntryType,
     pub symbols: Vec<Symbol>,
 }
 
+#[derive(Serialize, Deserialize)]
+pub struct InheritedEntry {
+    pub entry_type: EntryType,
+    pub names: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct InheritedMembers {
+    pub ancestor: String,
+    pub entries: Vec<InheritedEntry>,
+}
+
+#[derive(Serialize, Deserialize)]
 pub struct DocumentationData {
     pub source_file: String,
+    pub class_name: Option<String>,
+    pub extends: Option<String>,
+    pub tool: bool,
+    // The comment on the script header, describing the whole script
+    #[serde(default)]
+    pub text: Vec<String>,
+    #[serde(default)]
+    pub doc_comment: bool,
+    #[serde(default)]
+    pub tags: DocTags,
     pub entries: Vec<DocumentationEntry>,
+    pub inherited: Vec<InheritedMembers>,
+    #[serde(skip)]
+    pub declared_functions: Vec<String>,
+    // Lint rules disabled for the whole script, by a comment on the script header
+    #[serde(skip)]
+    pub allowed_lints: Vec<String>,
 }
 
-struct FileIterator<R: Read> {
-    reader: Lines<BufReader<R>>,
-    lineno: u32,
+// A logical line of code, which may span several lines of the file
+#[derive(Default)]
+struct Statement<'a> {
+    indentation: u32,
+    tokens: Vec<Token<'a>>,
+    comments: Vec<Token<'a>>,
 }
 
-impl<R: Read> FileIterator<R> {
-    fn new(r: R) -> FileIterator<R> {
-        FileIterator {
-            reader: BufReader::new(r).lines(),
-            lineno: 0,
+fn split_statements<'a>(
+    filename: &str,
+    source: &str,
+    tokens: &[Token<'a>],
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Vec<Statement<'a>> {
+    let mut statements = Vec::new();
+    let mut statement = Statement::default();
+    let mut indentation = Indentation::default();
+
+    for token in tokens {
+        match token.kind {
+            TokenKind::Newline => {
+                if !statement.tokens.is_empty() || !statement.comments.is_empty() {
+                    statements.push(std::mem::take(&mut statement));
+                }
+            }
+            TokenKind::Comment => statement.comments.push(*token),
+            _ => {
+                if statement.tokens.is_empty() {
+                    let line_start = source[..token.span.start].rfind('\n').map_or(0, |p| p + 1);
+                    let line = &source[line_start..token.span.start];
+                    let whitespace =
+                        &line[..line.len() - line.trim_start_matches([' ', '\t']).len()];
+                    statement.indentation =
+                        indentation.level(filename, whitespace, token.line, diagnostics);
+                }
+                statement.tokens.push(*token);
+            }
         }
     }
 
-    fn lineno(&self) -> u32 {
-        self.lineno
+    statements
+}
+
+// Columns a tab stands for, when it is compared to spaces
+const TAB_COLUMNS: u32 = 4;
+
+// How a script is indented. Like Godot, the first indented line decides whether it is indented
+// with tabs or spaces, and how many spaces make up a level.
+#[derive(Default)]
+struct Indentation {
+    style: Option<(char, u32, SourceSpan)>,
+    reported_mixed: bool,
+}
+
+impl Indentation {
+    fn level(
+        &mut self,
+        filename: &str,
+        whitespace: &str,
+        line: u32,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) -> u32 {
+        let Some(first) = whitespace.chars().next() else {
+            return 0;
+        };
+        let columns = whitespace
+            .chars()
+            .map(|c| if c == '\t' { TAB_COLUMNS } else { 1 })
+            .sum::<u32>();
+        let span = SourceSpan {
+            line,
+            column: 1,
+            end_line: line,
+            end_column: whitespace.chars().count() as u32 + 1,
+        };
+
+        let (character, width, style_span) = *self.style.get_or_insert(match first {
+            '\t' => ('\t', TAB_COLUMNS, span),
+            _ => (first, columns, span),
+        });
+
+        // Reported once, as every following line would be affected as well
+        if !self.reported_mixed && whitespace.chars().any(|c| c != character) {
+            self.reported_mixed = true;
+            let note = match character {
+                '\t' => "the script is indented with tabs here",
+                _ => "the script is indented with spaces here",
+            };
+            diagnostics.push(
+                Diagnostic::error(
+                    filename,
+                    span,
+                    "Mixed use of tabs and spaces for indentation".to_string(),
+                )
+                .with_note(style_span, note),
+            );
+        } else if columns % width != 0 {
+            // The level is rounded down, which may move the line into the wrong block
+            diagnostics.push(
+                Diagnostic::warning(
+                    filename,
+                    span,
+                    format!(
+                        "Indentation of {} columns is not a multiple of the {} columns of a level",
+                        columns, width
+                    ),
+                )
+                .with_note(
+                    style_span,
+                    "the first indented line sets the width of a level here",
+                ),
+            );
+        }
+
+        columns / width
     }
 }
 
-impl<R: Read> Iterator for FileIterator<R> {
-    type Item = Result<String, String>;
+// The comment lines in front of a declaration
+#[derive(Default)]
+struct Comments {
+    lines: Vec<String>,
+    // Set by '##' doc comments, only their BBCode markup is rendered
+    doc: bool,
+}
+
+impl Comments {
+    fn take(&mut self) -> (Vec<String>, bool) {
+        let comments = std::mem::take(self);
+        (comments.lines, comments.doc)
+    }
+
+    fn append(&mut self, other: &mut Comments) {
+        let (mut lines, doc) = other.take();
+        self.lines.append(&mut lines);
+        self.doc |= doc;
+    }
+}
 
-    fn next(&mut self) -> Option<Result<String, String>> {
-        if let Some(x) = self.reader.next() {
-            self.lineno += 1;
-            return Some(x.map_err(|e| e.to_string()));
+fn push_comment(
+    comment: &Token,
+    comment_buffer: &mut Comments,
+    override_visibility: &mut Option<bool>,
+    allowed_lints: &mut Vec<String>,
+) {
+    // Doc comments start with '##', the indentation after the first space is kept for code blocks
+    let text = comment.text.trim_start_matches('#');
+    let text = text.strip_prefix([' ', '\t']).unwrap_or(text).trim_end();
+
+    match text.trim() {
+        "[Show]" => *override_visibility = Some(true),
+        "[Hide]" => *override_visibility = Some(false),
+        x if x.starts_with("[Allow(") && x.ends_with(")]") => allowed_lints.extend(
+            x["[Allow(".len()..x.len() - ")]".len()]
+                .split(',')
+                .map(|rule| rule.trim().to_string()),
+        ),
+        x if x.starts_with("warning-ignore:") => (),
+        _ => {
+            comment_buffer.doc |= comment.text.starts_with("##");
+            comment_buffer.lines.push(text.to_string());
         }
-        None
     }
 }
 
-fn get_indentation_level(s: &str) -> u32 {
-    let mut i = 0;
-    for c in s.chars() {
-        if c != '\t' {
-            return i;
+// Points at the given tokens, or at the span of the whole statement if there are none
+fn syntax_error(filename: &str, span: SourceSpan, tokens: &[Token]) -> Diagnostic {
+    let span = match tokens {
+        [] => span,
+        tokens => SourceSpan::from_tokens(tokens),
+    };
+    Diagnostic::error(
+        filename,
+        span,
+        format!("invalid syntax '{}'", tokens_text(tokens)),
+    )
+}
+
+// Splits the tokens at the given symbol, ignoring symbols inside of brackets
+fn split_tokens<'a, 'b>(tokens: &'b [Token<'a>], separator: &str) -> Vec<&'b [Token<'a>]> {
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+
+    for (i, token) in tokens.iter().enumerate() {
+        if token.kind != TokenKind::Symbol {
+            continue;
+        }
+        match token.text {
+            "(" | "[" | "{" => depth += 1,
+            ")" | "]" | "}" => depth -= 1,
+            x if x == separator && depth == 0 => {
+                parts.push(&tokens[start..i]);
+                start = i + 1;
+            }
+            _ => (),
         }
-        i += 1;
     }
+    parts.push(&tokens[start..]);
 
-    return i;
+    parts
 }
 
-fn get_comment<'a>(
-    filename: &str,
-    lineno: u32,
-    line: &'a str,
-    parentheses: &mut Vec<char>,
-) -> Result<(&'a str, Option<&'a str>), String> {
-    let pos = find(filename, lineno, line, '#', parentheses)?;
+// Returns the position of the first token outside of brackets matching the predicate
+fn find_token(tokens: &[Token], predicate: impl Fn(&Token) -> bool) -> Option<usize> {
+    let mut depth = 0;
+
+    for (i, token) in tokens.iter().enumerate() {
+        if depth == 0 && predicate(token) {
+            return Some(i);
+        }
+        if token.kind == TokenKind::Symbol {
+            match token.text {
+                "(" | "[" | "{" => depth += 1,
+                ")" | "]" | "}" => depth -= 1,
+                _ => (),
+            }
+        }
+    }
+
+    None
+}
+
+// Returns the tokens inside of the brackets opened by the first token, and the tokens after them
+fn bracket_contents<'a, 'b>(tokens: &'b [Token<'a>]) -> Option<(&'b [Token<'a>], &'b [Token<'a>])> {
+    let mut depth = 0;
 
-    if let Some(pos) = pos {
-        return Ok((line[..pos].trim_end(), Some(line[pos + 1..].trim())));
+    for (i, token) in tokens.iter().enumerate() {
+        if token.kind != TokenKind::Symbol {
+            continue;
+        }
+        match token.text {
+            "(" | "[" | "{" => depth += 1,
+            ")" | "]" | "}" => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some((&tokens[1..i], &tokens[i + 1..]));
+                }
+            }
+            _ => (),
+        }
     }
 
-    Ok((line, None))
+    None
 }
 
 #[derive(Default)]
 struct ClassFrame {
+    class_name: Option<String>,
+    extends: Option<String>,
+    tool: bool,
+    // Comments on the script header
+    header_comments: Comments,
+    declared_functions: Vec<String>,
+    allowed_lints: Vec<String>,
+    // The kind of the last property while the block of its accessors may follow, along with
+    // the indentation of the block once it is known
+    property_block: Option<(EntryType, Option<u32>)>,
+    // The section of the inspector the following exports are shown in
+    section: InspectorSection,
+
     classes: Vec<Symbol>,
     signals: Vec<Symbol>,
     functions: Vec<Symbol>,
@@ -171,366 +694,547 @@ struct ClassFrame {
     enums: Vec<Symbol>,
 }
 
-#[derive(Default)]
-struct EnumFrame {
-    last_value: isize,
-    values: Vec<EnumValue>,
-}
-
 enum Mode {
     Normal(ClassFrame),
-    Enum(String, EnumFrame),
-    Class(String, (u32, Option<u32>), ClassFrame, Vec<String>),
-}
-
-fn get_constant(stack: &Vec<Mode>, raw: &str) -> Option<String> {
-    for frame in stack.iter().rev() {
-        match frame {
-            Mode::Class(_, _, class_frame, _) | Mode::Normal(class_frame) => {
-                for v in &class_frame.constants {
-                    if v.name == raw {
-                        if let Some(SymbolArgs::VariableArgs(VariableArgStruct {
-                            assignment,
-                            ..
-                        })) = &v.arg
-                        {
-                            return assignment.clone();
-                        }
-                    }
+    Class(String, (u32, Option<u32>), ClassFrame, Comments, SourceSpan),
+}
+
+fn get_constant(frame: &ClassFrame, stack: &[Mode], raw: &str) -> Option<String> {
+    let frames = stack.iter().rev().map(|mode| match mode {
+        Mode::Class(_, _, class_frame, _, _) | Mode::Normal(class_frame) => class_frame,
+    });
+
+    for class_frame in std::iter::once(frame).chain(frames) {
+        for v in &class_frame.constants {
+            if v.name == raw {
+                if let Some(SymbolArgs::VariableArgs(VariableArgStruct { assignment, .. })) = &v.arg
+                {
+                    return assignment.clone();
                 }
             }
-            _ => {}
         }
     }
 
     None
 }
 
+#[allow(clippy::too_many_arguments)]
 fn parse_enum(
+    filename: &str,
     settings: &Settings,
-    stack: &Vec<Mode>,
-    values: &str,
-    enum_frame: &mut EnumFrame,
+    frame: &ClassFrame,
+    stack: &[Mode],
+    statement: &Statement,
+    values: &[Token],
+    comment_buffer: &mut Comments,
     override_visibility: &mut Option<bool>,
-    comment_buffer: &mut Vec<String>,
-) -> Result<(), String> {
-    for v in values.split(',') {
-        let mut arg_iterator = v.split('=');
-
-        let name = arg_iterator
-            .next()
-            .ok_or("Expected name for enum value")?
-            .trim();
-        if name.is_empty() {
-            continue;
+    allowed_lints: &mut Vec<String>,
+) -> Result<Vec<EnumValue>, Diagnostic> {
+    let span = SourceSpan::from_tokens(&statement.tokens);
+    let mut enum_values = Vec::new();
+    let mut last_value = 0;
+    let mut comments = statement.comments.iter().peekable();
+
+    let values = split_tokens(values, ",")
+        .into_iter()
+        .filter(|v| !v.is_empty())
+        .collect::<Vec<_>>();
+    for (i, v) in values.iter().enumerate() {
+        let name = v[0];
+        if name.kind != TokenKind::Identifier {
+            return Err(syntax_error(filename, span, v));
         }
-        let value = arg_iterator
-            .next()
-            .and_then(|x| {
-                let raw = x.trim();
-                let res = raw.parse();
-                if let Err(_) = res {
-                    let val = get_constant(stack, raw);
-
-                    if let Some(v) = val {
-                        return Some(v.parse().map_err(|_| {
-                            format!(
-                                "Constant '{}' of value '{}' is not a valid enum value",
-                                raw, v
-                            )
-                        }));
-                    }
-
-                    return Some(Err(format!("'{}' is not a valid enum value", raw)));
-                }
 
-                Some(Ok(res.unwrap()))
-            })
-            .unwrap_or(Ok(enum_frame.last_value))?;
+        // Comments before a value and behind it on the same line document that value
+        let next_start = values.get(i + 1).map(|n| n[0].span.start);
+        let mut text = Comments::default();
+        let mut value_visibility = None;
+        while let Some(comment) = comments.next_if(|c| {
+            c.span.start < name.span.start
+                || (c.line == name.line && next_start.is_none_or(|n| c.span.start < n))
+        }) {
+            push_comment(comment, &mut text, &mut value_visibility, &mut Vec::new());
+        }
 
-        enum_frame.last_value = value + 1;
+        let value = match v.get(1) {
+            Some(t) if t.is_symbol("=") => {
+                let raw = tokens_text(&v[2..]);
+                match raw.parse() {
+                    Ok(value) => value,
+                    Err(_) => match get_constant(frame, stack, &raw) {
+                        Some(constant) => constant.parse().map_err(|_| {
+                            Diagnostic::error(
+                                filename,
+                                SourceSpan::from_tokens(v),
+                                format!(
+                                    "Constant '{}' of value '{}' is not a valid enum value",
+                                    raw, constant
+                                ),
+                            )
+                        })?,
+                        None => {
+                            return Err(Diagnostic::error(
+                                filename,
+                                SourceSpan::from_tokens(v),
+                                format!("'{}' is not a valid enum value", raw),
+                            ))
+                        }
+                    },
+                }
+            }
+            None => last_value,
+            Some(_) => return Err(syntax_error(filename, span, v)),
+        };
+        last_value = value + 1;
 
-        if (!name.starts_with("_") || settings.show_prefixed) && override_visibility.unwrap_or(true)
+        if (!name.text.starts_with("_") || settings.show_prefixed)
+            && value_visibility.unwrap_or(true)
         {
-            enum_frame.values.push(EnumValue {
-                name: name.to_string(),
-                value: value,
-                text: comment_buffer.drain(..).collect(),
+            let (text, doc_comment) = text.take();
+            enum_values.push(EnumValue {
+                name: name.text.to_string(),
+                value,
+                text,
+                doc_comment,
+                span: SourceSpan::from_tokens(v),
             });
         }
     }
 
-    Ok(())
+    // The remaining comments document the enum itself
+    for comment in comments {
+        push_comment(comment, comment_buffer, override_visibility, allowed_lints);
+    }
+
+    Ok(enum_values)
+}
+
+// Adds a class, whose body ended, to the frame enclosing it
+fn close_class(
+    stack: &mut [Mode],
+    name: String,
+    mut frame: ClassFrame,
+    mut comments: Comments,
+    span: SourceSpan,
+) {
+    let extends = frame.extends.take();
+    let declared_functions = std::mem::take(&mut frame.declared_functions);
+    let allowed_lints = std::mem::take(&mut frame.allowed_lints);
+    let mut entries = Vec::new();
+    add_entries(&mut entries, frame);
+
+    if let Some(Mode::Normal(parent) | Mode::Class(_, _, parent, _, _)) = stack.last_mut() {
+        let (text, doc_comment) = comments.take();
+        parent.classes.push(Symbol {
+            name,
+            arg: Some(SymbolArgs::ClassArgs(ClassArgStruct {
+                extends,
+                entries,
+                inherited: Vec::new(),
+                declared_functions,
+            })),
+            text,
+            doc_comment,
+            tags: DocTags::default(),
+            span,
+            allowed_lints,
+        });
+    }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn parse_line(
     filename: &str,
-    lineno: u32,
     settings: &Settings,
     mut mode: Mode,
     stack: &mut Vec<Mode>,
-    line: String,
+    statement: &Statement,
     override_visibility: &mut Option<bool>,
-    comment_buffer: &mut Vec<String>,
-    indentation_level: u32,
-) -> Result<(), String> {
-    match mode {
-        Mode::Enum(ref name, ref mut enum_frame) => {
-            let end = line.find('}');
-            let slice = match end {
-                Some(x) => &line[..x],
-                None => &line,
-            };
-
-            parse_enum(
-                settings,
-                stack,
-                slice,
-                enum_frame,
-                override_visibility,
-                comment_buffer,
-            )?;
-
-            if end.is_some() {
-                let name_string = name.to_string();
-                let values = enum_frame.values.drain(..).collect();
-                match stack.last_mut() {
-                    Some(Mode::Normal(ref mut frame))
-                    | Some(Mode::Class(_, _, ref mut frame, _)) => frame.enums.push(Symbol {
-                        name: name_string,
-                        arg: Some(SymbolArgs::EnumArgs(values)),
-                        text: comment_buffer.drain(..).collect(),
-                    }),
-                    Some(Mode::Enum(_, _)) => {
-                        panic!("[parser.rs] Unexpected Enum value after completed enum")
-                    }
-                    None => panic!("[parser.rs] Unexpected end of parsing_mode stack"),
-                }
-            } else {
-                stack.push(mode);
+    allowed_lints: &mut Vec<String>,
+    comment_buffer: &mut Comments,
+    annotation_buffer: &mut Vec<Annotation>,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let indentation_level = statement.indentation;
+
+    let parsed = match mode {
+        Mode::Class(_, (old_indent, ref mut indent), ref mut frame, _, class_span) => {
+            if indent.is_none() && indentation_level <= old_indent {
+                // The class ends without any members
+                diagnostics.push(
+                    Diagnostic::error(
+                        filename,
+                        SourceSpan::from_tokens(&statement.tokens),
+                        "Indented block expected".to_string(),
+                    )
+                    .with_note(class_span, "class declared here"),
+                );
+                *indent = Some(old_indent + 1);
             }
-        }
+            let indent = *indent.get_or_insert(indentation_level);
 
-        Mode::Class(ref mut name, (ref old_indent, ref mut indent), ref mut frame, _) => {
-            if indent.is_none() {
-                if indentation_level > *old_indent {
-                    *indent = Some(indentation_level);
-                } else {
-                    return Err(format!(
-                        "Failed to parse {}, line {}: Indented block expected",
-                        filename, lineno
-                    ));
-                }
-            }
-            let indent = indent.unwrap();
             if indentation_level == indent {
-                let new_frame = parse_class_content(
+                parse_class_content(
                     filename,
-                    lineno,
-                    &line.trim(),
-                    indentation_level,
+                    statement,
                     frame,
                     comment_buffer,
                     settings,
                     override_visibility,
-                    &stack,
-                )?;
-                stack.push(mode);
-                if let Some(new_frame) = new_frame {
-                    stack.push(new_frame);
-                }
+                    allowed_lints,
+                    annotation_buffer,
+                    stack,
+                )
             } else if indentation_level < indent {
-                let mut entries = Vec::new();
-                let name = name.to_string();
-                let (frame, comments) = match mode {
-                    Mode::Class(_, _, frame, comments) => (frame, comments),
-                    _ => panic!(),
-                };
-                add_entries(&mut entries, frame);
-
-                match stack.last_mut() {
-                    Some(Mode::Normal(ref mut frame))
-                    | Some(Mode::Class(_, _, ref mut frame, _)) => frame.classes.push(Symbol {
-                        name: name,
-                        arg: Some(SymbolArgs::ClassArgs(entries)),
-                        text: comments,
-                    }),
-                    Some(Mode::Enum(_, _)) => {
-                        panic!("[parser.rs] Unexpected Enum value after completed class")
-                    }
-                    None => panic!("[parser.rs] Unexpected end of parsing_mode stack"),
+                if let Mode::Class(name, _, frame, text, span) = mode {
+                    close_class(stack, name, frame, text, span);
                 }
 
                 return parse_line(
                     filename,
-                    lineno,
                     settings,
                     stack.pop().unwrap(),
                     stack,
-                    line,
+                    statement,
                     override_visibility,
+                    allowed_lints,
                     comment_buffer,
-                    indentation_level,
+                    annotation_buffer,
+                    diagnostics,
                 );
+            } else {
+                // Lines indented deeper belong to the body of a member
+                parse_property_block(filename, statement, frame)
             }
         }
 
         Mode::Normal(ref mut frame) => {
-            let new_frame = parse_class_content(
-                filename,
-                lineno,
-                line.as_str(),
-                indentation_level,
-                frame,
-                comment_buffer,
-                settings,
-                override_visibility,
-                &stack,
-            )?;
-            stack.push(mode);
-            if let Some(new_frame) = new_frame {
-                stack.push(new_frame);
+            // Indented lines belong to the body of a function
+            if indentation_level == 0 {
+                parse_class_content(
+                    filename,
+                    statement,
+                    frame,
+                    comment_buffer,
+                    settings,
+                    override_visibility,
+                    allowed_lints,
+                    annotation_buffer,
+                    stack,
+                )
+            } else {
+                parse_property_block(filename, statement, frame)
             }
         }
+    };
+
+    // Statements that fail to parse are skipped
+    stack.push(mode);
+    match parsed {
+        Ok(new_frame) => stack.extend(new_frame),
+        Err(diagnostic) => diagnostics.push(diagnostic),
     }
+}
 
-    Ok(())
+// Records the accessors in the block of a Godot 4 property, their bodies are skipped
+fn parse_property_block(
+    filename: &str,
+    statement: &Statement,
+    frame: &mut ClassFrame,
+) -> Result<Option<Mode>, Diagnostic> {
+    let Some((entry_type, indentation)) = &mut frame.property_block else {
+        return Ok(None);
+    };
+    if *indentation.get_or_insert(statement.indentation) != statement.indentation {
+        return Ok(None);
+    }
+
+    let symbol = match entry_type {
+        EntryType::EXPORT => frame.exports.last_mut(),
+        _ => frame.variables.last_mut(),
+    };
+    let Some(symbol) = symbol else {
+        return Ok(None);
+    };
+    let (setter, getter) = match &mut symbol.arg {
+        Some(SymbolArgs::VariableArgs(variable)) => (&mut variable.setter, &mut variable.getter),
+        Some(SymbolArgs::ExportArgs(export)) => (&mut export.setter, &mut export.getter),
+        _ => return Ok(None),
+    };
+
+    parse_accessors(
+        filename,
+        SourceSpan::from_tokens(&statement.tokens),
+        &symbol.name,
+        &statement.tokens,
+        setter,
+        getter,
+    )?;
+    Ok(None)
 }
 
+/// Parses the script at the given path, relative to the input directory. Problems are added to
+/// the diagnostics, parsing resumes at the next statement after each of them.
 pub fn parse_file(
-    filename: &str,
-    f: File,
+    path: &Path,
+    mut f: File,
     settings: &Settings,
+    diagnostics: &mut Vec<Diagnostic>,
 ) -> Result<DocumentationData, String> {
-    let mut parsing_mode = vec![Mode::Normal(ClassFrame::default())];
+    let filename = path.display().to_string();
+    let filename = filename.as_str();
 
-    let mut comment_buffer: Vec<String> = Vec::new();
-    let mut override_visibility = None;
-    let mut open_parentheses = Vec::new();
-
-    let mut lines = FileIterator::new(f);
-    while let Some(mut current_line) = lines.next() {
-        let mut full_line: String = String::new();
-
-        // Parse the full statement with normal opening parentheses '(' all closed
-        loop {
-            let mut partial_line = current_line?;
-
-            // Backslashes at the end of a line ignore the newline
-            while partial_line.ends_with("\\") && !partial_line.contains('#') {
-                partial_line.remove(partial_line.len() - 1);
-                partial_line += &lines
-                    .next()
-                    .ok_or("Unexpected eof, expected newline after \\".to_string())??
-                    .as_str()
-                    .trim()
-            }
-            let (partial_line, comment) = get_comment(
-                filename,
-                lines.lineno(),
-                &partial_line,
-                &mut open_parentheses,
-            )?;
+    let mut source = String::new();
+    f.read_to_string(&mut source)
+        .map_err(|e| format!("Failed to read {}: {}", filename, e))?;
 
-            if let Some(comment) = comment {
-                override_visibility = match comment {
-                    "[Show]" => Some(true),
-                    "[Hide]" => Some(false),
-                    _ => override_visibility,
-                };
-                if !comment.starts_with("warning-ignore:") {
-                    comment_buffer.push(comment.to_string());
-                }
-            }
+    Ok(parse_source(path, &source, settings, diagnostics))
+}
 
-            full_line += &partial_line;
+fn parse_source(
+    path: &Path,
+    source: &str,
+    settings: &Settings,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> DocumentationData {
+    let filename = path.display().to_string();
+    let filename = filename.as_str();
+    let tokens = tokenize(filename, source, diagnostics);
 
-            if !open_parentheses.contains(&'(') {
-                break;
-            }
+    let mut parsing_mode = vec![Mode::Normal(ClassFrame::default())];
 
-            current_line = lines
-                .next()
-                .ok_or("Unexpected eof, mismatched parentheses".to_string())?
-                .map(|x| x.trim().to_string());
+    let mut comment_buffer = Comments::default();
+    let mut override_visibility = None;
+    let mut allowed_lints = Vec::new();
+    let mut annotation_buffer = Vec::new();
+
+    for statement in split_statements(filename, source, &tokens, diagnostics) {
+        // The comments inside of enums belong to their values
+        if !statement.tokens.iter().any(|t| t.is_keyword("enum")) {
+            for comment in &statement.comments {
+                push_comment(
+                    comment,
+                    &mut comment_buffer,
+                    &mut override_visibility,
+                    &mut allowed_lints,
+                );
+            }
         }
 
-        let indentation_level = get_indentation_level(full_line.as_str());
-        if !full_line.trim().is_empty() {
+        if !statement.tokens.is_empty() {
             parse_line(
                 filename,
-                lines.lineno(),
                 settings,
                 parsing_mode.pop().unwrap(),
                 &mut parsing_mode,
-                full_line,
+                &statement,
                 &mut override_visibility,
+                &mut allowed_lints,
                 &mut comment_buffer,
-                indentation_level,
-            )?;
-            comment_buffer.clear();
-            override_visibility = None;
+                &mut annotation_buffer,
+                diagnostics,
+            );
+            if annotation_buffer.is_empty() {
+                comment_buffer = Comments::default();
+                override_visibility = None;
+                allowed_lints.clear();
+            }
         }
     }
 
-    while parsing_mode.len() > 0 {
-        match parsing_mode.pop().unwrap() {
-            Mode::Class(name, _, frame, text) => {
-                let class_name = name;
-                let mut entries = Vec::new();
-                add_entries(&mut entries, frame);
-
-                let comments = text;
-                match parsing_mode.last_mut() {
-                    Some(Mode::Normal(ref mut frame))
-                    | Some(Mode::Class(_, _, ref mut frame, _)) => frame.classes.push(Symbol {
-                        name: class_name,
-                        arg: Some(SymbolArgs::ClassArgs(entries)),
-                        text: comments,
-                    }),
-                    Some(Mode::Enum(_, _)) => {
-                        panic!("[parser.rs] Unexpected Enum value after completed class")
-                    }
-                    None => panic!("[parser.rs] Unexpected end of parsing_mode stack"),
-                }
+    // Classes still open at the end of the file end there
+    let mut frame = ClassFrame::default();
+    while let Some(mode) = parsing_mode.pop() {
+        match mode {
+            Mode::Class(name, _, class_frame, text, span) => {
+                close_class(&mut parsing_mode, name, class_frame, text, span)
             }
-            Mode::Enum(name, enum_frame) => {
-                let name_string = name.to_string();
-                let values = enum_frame.values;
-                match parsing_mode.last_mut() {
-                    Some(Mode::Normal(ref mut frame))
-                    | Some(Mode::Class(_, _, ref mut frame, _)) => frame.enums.push(Symbol {
-                        name: name_string,
-                        arg: Some(SymbolArgs::EnumArgs(values)),
-                        text: comment_buffer.drain(..).collect(),
-                    }),
-                    Some(Mode::Enum(_, _)) => {
-                        panic!("[parser.rs] Unexpected Enum value after completed enum")
+            Mode::Normal(script_frame) => frame = script_frame,
+        }
+    }
+
+    let class_name = frame.class_name.take();
+    let extends = frame.extends.take();
+    let tool = frame.tool;
+    let (mut text, doc_comment) = frame.header_comments.take();
+    let tags = parse_doc_tags(&mut text);
+    let declared_functions = std::mem::take(&mut frame.declared_functions);
+    let allowed_lints = std::mem::take(&mut frame.allowed_lints);
+    let mut entries = Vec::new();
+    add_entries(&mut entries, frame);
+    extract_doc_tags(filename, &mut entries, diagnostics);
+
+    DocumentationData {
+        source_file: path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string(),
+        class_name,
+        extends,
+        tool,
+        text,
+        doc_comment,
+        tags,
+        entries,
+        inherited: Vec::new(),
+        declared_functions,
+        allowed_lints,
+    }
+}
+
+// Splits the tags off the comment of a symbol, text following a tag line belongs to that tag
+fn parse_doc_tags(text: &mut Vec<String>) -> DocTags {
+    let mut tags = DocTags::default();
+    let mut current: Option<&mut String> = None;
+    let mut description = Vec::new();
+
+    for line in text.drain(..) {
+        let trimmed = line.trim();
+        if !trimmed.starts_with('@') {
+            match current {
+                Some(ref mut tag) if !trimmed.is_empty() => {
+                    if !tag.is_empty() {
+                        tag.push(' ');
                     }
-                    None => panic!("[parser.rs] Unexpected end of parsing_mode stack"),
+                    tag.push_str(trimmed);
                 }
+                Some(_) => (),
+                None => description.push(line),
             }
+            continue;
+        }
 
-            Mode::Normal(frame) => {
-                let mut entries = Vec::new();
-                add_entries(&mut entries, frame);
+        let (tag, rest) = match trimmed.find(char::is_whitespace) {
+            Some(pos) => (&trimmed[..pos], trimmed[pos..].trim()),
+            None => (trimmed, ""),
+        };
 
-                return Ok(DocumentationData {
-                    source_file: filename.to_string(),
-                    entries: entries,
+        current = match tag {
+            "@param" => {
+                let (name, rest) = match rest.find(char::is_whitespace) {
+                    Some(pos) => (&rest[..pos], rest[pos..].trim()),
+                    None => (rest, ""),
+                };
+                tags.params.push(ParamDoc {
+                    name: name.to_string(),
+                    text: rest.to_string(),
                 });
+                tags.params.last_mut().map(|p| &mut p.text)
             }
-        }
+            "@return" | "@returns" => Some(tags.returns.insert(rest.to_string())),
+            "@deprecated" => Some(tags.deprecated.insert(rest.to_string())),
+            "@since" => Some(tags.since.insert(rest.to_string())),
+            "@experimental" => Some(tags.experimental.insert(rest.to_string())),
+            _ => {
+                description.push(line.clone());
+                None
+            }
+        };
     }
 
-    panic!()
+    *text = description;
+    tags
 }
 
-fn add_entries(entries: &mut Vec<DocumentationEntry>, frame: ClassFrame) {
-    if !frame.classes.is_empty() {
-        entries.push(DocumentationEntry {
-            entry_type: EntryType::CLASS,
-            symbols: frame.classes,
+fn extract_doc_tags(
+    filename: &str,
+    entries: &mut [DocumentationEntry],
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    for symbol in entries.iter_mut().flat_map(|e| e.symbols.iter_mut()) {
+        symbol.tags = parse_doc_tags(&mut symbol.text);
+
+        let (kind, arguments) = match &mut symbol.arg {
+            Some(SymbolArgs::FunctionArgs(FunctionArgStruct { arguments, .. })) => {
+                ("function", arguments)
+            }
+            Some(SymbolArgs::SignalArgs(arguments)) => ("signal", arguments),
+            Some(SymbolArgs::ClassArgs(ClassArgStruct { entries, .. })) => {
+                extract_doc_tags(filename, entries, diagnostics);
+                continue;
+            }
+            _ => {
+                if !symbol.tags.params.is_empty() {
+                    diagnostics.push(Diagnostic::warning(
+                        filename,
+                        symbol.span,
+                        format!(
+                            "@param used on '{}', which is neither a function nor a signal",
+                            symbol.name
+                        ),
+                    ));
+                }
+                continue;
+            }
+        };
+
+        for param in &symbol.tags.params {
+            if !arguments.iter().any(|a| a.name == param.name) {
+                diagnostics.push(Diagnostic::warning(
+                    filename,
+                    symbol.span,
+                    format!(
+                        "@param '{}' of {} '{}' does not name an argument",
+                        param.name, kind, symbol.name
+                    ),
+                ));
+            }
+        }
+    }
+}
+
+// The inspector shows the exports outside of any group in front of the groups of their
+// category, so exports following the end of a group are moved in front of it
+fn sort_by_section(exports: &mut Vec<Symbol>) {
+    // Sections are ranked in the order they appear in, the lack of one comes first
+    fn rank<'a>(
+        seen: &mut Vec<[Option<&'a str>; 3]>,
+        path: [Option<&'a str>; 3],
+        depth: usize,
+    ) -> usize {
+        if path[depth].is_none() {
+            return 0;
+        }
+        let path = [0, 1, 2].map(|i| if i <= depth { path[i] } else { None });
+        match seen.iter().position(|p| *p == path) {
+            Some(pos) => pos + 1,
+            None => {
+                seen.push(path);
+                seen.len()
+            }
+        }
+    }
+
+    let mut seen = Vec::new();
+
+    let keys = exports
+        .iter()
+        .map(|symbol| match &symbol.arg {
+            Some(SymbolArgs::ExportArgs(export)) => {
+                let section = &export.section;
+                let path = [
+                    section.category.as_deref(),
+                    section.group.as_ref().map(|g| g.name.as_str()),
+                    section.subgroup.as_ref().map(|g| g.name.as_str()),
+                ];
+                [0, 1, 2].map(|depth| rank(&mut seen, path, depth))
+            }
+            _ => [0; 3],
+        })
+        .collect::<Vec<_>>();
+
+    let mut sorted = keys.into_iter().zip(exports.drain(..)).collect::<Vec<_>>();
+    sorted.sort_by_key(|(key, _)| *key);
+    exports.extend(sorted.into_iter().map(|(_, symbol)| symbol));
+}
+
+fn add_entries(entries: &mut Vec<DocumentationEntry>, mut frame: ClassFrame) {
+    sort_by_section(&mut frame.exports);
+    if !frame.classes.is_empty() {
+        entries.push(DocumentationEntry {
+            entry_type: EntryType::CLASS,
+            symbols: frame.classes,
         })
     }
     if !frame.enums.is_empty() {
@@ -571,625 +1275,1297 @@ fn add_entries(entries: &mut Vec<DocumentationEntry>, frame: ClassFrame) {
     }
 }
 
+fn parse_class_header(
+    filename: &str,
+    span: SourceSpan,
+    tokens: &[Token],
+) -> Result<(String, Option<String>), Diagnostic> {
+    // Everything after the colon is the body of a one line class
+    let tokens = match find_token(tokens, |t| t.is_symbol(":")) {
+        Some(pos) => &tokens[..pos],
+        None => tokens,
+    };
+
+    let (name, extends) = match find_token(tokens, |t| t.is_keyword("extends")) {
+        Some(pos) => (&tokens[..pos], Some(tokens_text(&tokens[pos + 1..]))),
+        None => (tokens, None),
+    };
+    if name.is_empty() {
+        return Err(syntax_error(filename, span, tokens));
+    }
+
+    Ok((tokens_text(name), extends))
+}
+
+#[allow(clippy::too_many_arguments)]
 fn parse_class_content(
     filename: &str,
-    lineno: u32,
-    line: &str,
-    indent: u32,
+    statement: &Statement,
     frame: &mut ClassFrame,
-    comment_buffer: &mut Vec<String>,
+    comment_buffer: &mut Comments,
     settings: &Settings,
     override_visibility: &mut Option<bool>,
-    parsing_mode: &Vec<Mode>,
-) -> Result<Option<Mode>, String> {
-    if line.starts_with("class ") {
-        let name = line[5..].split(':').next().unwrap().trim().to_string();
+    allowed_lints: &mut Vec<String>,
+    annotation_buffer: &mut Vec<Annotation>,
+    parsing_mode: &[Mode],
+) -> Result<Option<Mode>, Diagnostic> {
+    let span = SourceSpan::from_tokens(&statement.tokens);
+    frame.property_block = None;
+    // Buffered annotations are dropped along with a declaration, which fails to parse
+    let buffered = std::mem::take(annotation_buffer);
+    let (mut annotations, tokens) = parse_annotations(filename, &statement.tokens)?;
+    if annotations.iter().any(|a| a.name == "tool") {
+        frame.tool = true;
+        frame.header_comments.append(comment_buffer);
+        annotations.retain(|a| a.name != "tool");
+    }
+    annotations.retain(|a| !enter_section(&mut frame.section, a));
+    if tokens.is_empty() {
+        // Annotations on their own line apply to the next declaration
+        *annotation_buffer = buffered;
+        annotation_buffer.extend(annotations);
+        return Ok(None);
+    }
+    let annotations = buffered.into_iter().chain(annotations).collect::<Vec<_>>();
 
-        if !name.starts_with("_") || settings.show_prefixed {
-            return Ok(Some(Mode::Class(
-                name,
-                (indent, None),
-                ClassFrame::default(),
-                comment_buffer.drain(..).collect(),
-            )));
-        }
-    } else if line.starts_with("signal ") {
-        let name = line[6..].trim().to_string();
-        if (!name.starts_with("_") || settings.show_prefixed) && override_visibility.unwrap_or(true)
-        {
-            frame.signals.push(Symbol {
-                name: name,
-                arg: None,
-                text: comment_buffer.drain(..).collect(),
-            });
-        }
-    } else if line.starts_with("func ") {
-        let mut name = String::new();
-        let mut arguments = Vec::new();
-        let mut super_arguments = None;
-        let mut return_type = None;
-
-        parse_function(
-            &line[4..],
-            &mut name,
-            &mut arguments,
-            &mut super_arguments,
-            &mut return_type,
-        )?;
-
-        if (!name.starts_with("_") || settings.show_prefixed) && override_visibility.unwrap_or(true)
-        {
-            frame.functions.push(Symbol {
-                name: name,
-                arg: Some(SymbolArgs::FunctionArgs(FunctionArgStruct {
-                    arguments: arguments,
-                    super_arguments: super_arguments,
-                    return_type: return_type,
-                })),
-                text: comment_buffer.drain(..).collect(),
-            });
+    let keyword = tokens[0];
+    let declaration = &tokens[1..];
+    if keyword.kind != TokenKind::Identifier {
+        return Ok(None);
+    }
+
+    // Rules allowed on the script header apply to the whole script
+    if matches!(keyword.text, "tool" | "extends" | "class_name") {
+        frame.allowed_lints.append(allowed_lints);
+        frame.header_comments.append(comment_buffer);
+    }
+
+    match keyword.text {
+        "tool" if declaration.is_empty() => frame.tool = true,
+        "extends" => frame.extends = Some(tokens_text(declaration)),
+        "class_name" => {
+            let (name, extends) = parse_class_header(filename, span, declaration)?;
+            // Godot 3 allows an icon path after the class name
+            let name = name.split(',').next().unwrap().trim().to_string();
+
+            frame.class_name = Some(name);
+            if extends.is_some() {
+                frame.extends = extends;
+            }
         }
-    } else if line.starts_with("var ") {
-        let mut name = String::new();
-        let mut value_type = None;
-        let mut assignment = None;
-        let mut setter = None;
-        let mut getter = None;
-        parse_assignment(
-            filename,
-            lineno,
-            &line[4..],
-            &mut name,
-            &mut value_type,
-            &mut assignment,
-            &mut setter,
-            &mut getter,
-        )?;
-
-        if (!name.starts_with("_") || settings.show_prefixed) && override_visibility.unwrap_or(true)
-        {
-            frame.variables.push(Symbol {
-                name: name,
-                arg: Some(SymbolArgs::VariableArgs(VariableArgStruct {
-                    value_type: value_type,
-                    assignment: assignment,
-                    setter: setter,
-                    getter: getter,
-                })),
-                text: comment_buffer.drain(..).collect(),
-            });
+        "class" => {
+            let (name, extends) = parse_class_header(filename, span, declaration)?;
+
+            if !name.starts_with("_") || settings.show_prefixed {
+                return Ok(Some(Mode::Class(
+                    name,
+                    (statement.indentation, None),
+                    ClassFrame {
+                        extends,
+                        allowed_lints: std::mem::take(allowed_lints),
+                        ..ClassFrame::default()
+                    },
+                    std::mem::take(comment_buffer),
+                    span,
+                )));
+            }
         }
-    } else if line.starts_with("const ") {
-        let mut name = String::new();
-        let mut value_type = None;
-        let mut assignment = None;
-        let mut setter = None;
-        let mut getter = None;
-        parse_assignment(
-            filename,
-            lineno,
-            &line[6..],
-            &mut name,
-            &mut value_type,
-            &mut assignment,
-            &mut setter,
-            &mut getter,
-        )?;
-
-        if (!name.starts_with("_") || settings.show_prefixed) && override_visibility.unwrap_or(true)
-        {
-            frame.constants.push(Symbol {
-                name: name,
-                arg: Some(SymbolArgs::VariableArgs(VariableArgStruct {
-                    value_type: value_type,
-                    assignment: assignment,
-                    setter: setter,
-                    getter: getter,
-                })),
-                text: comment_buffer.drain(..).collect(),
-            });
+        "signal" => {
+            let mut name = String::new();
+            let mut arguments = Vec::new();
+            parse_signal(filename, span, declaration, &mut name, &mut arguments)?;
+
+            if (!name.starts_with("_") || settings.show_prefixed)
+                && override_visibility.unwrap_or(true)
+            {
+                let (text, doc_comment) = comment_buffer.take();
+                frame.signals.push(Symbol {
+                    name,
+                    arg: Some(SymbolArgs::SignalArgs(arguments)),
+                    text,
+                    doc_comment,
+                    tags: DocTags::default(),
+                    span,
+                    allowed_lints: std::mem::take(allowed_lints),
+                });
+            }
         }
-    } else if line.starts_with("export") {
-        let pos = line.find(" var ");
-        let open_paren = line.find('(');
-        let close_paren = line.find(')');
-        if pos.is_none() {
-            return Err(format!("Invalid syntax: {}", line));
+        "func" => {
+            let mut name = String::new();
+            let mut arguments = Vec::new();
+            let mut super_arguments = None;
+            let mut return_type = None;
+
+            parse_function(
+                filename,
+                span,
+                declaration,
+                &mut name,
+                &mut arguments,
+                &mut super_arguments,
+                &mut return_type,
+            )?;
+            frame.declared_functions.push(name.clone());
+
+            if (!name.starts_with("_") || settings.show_prefixed)
+                && override_visibility.unwrap_or(true)
+            {
+                let (text, doc_comment) = comment_buffer.take();
+                frame.functions.push(Symbol {
+                    name,
+                    arg: Some(SymbolArgs::FunctionArgs(FunctionArgStruct {
+                        arguments,
+                        super_arguments,
+                        return_type,
+                        overrides: None,
+                        engine_virtual: false,
+                    })),
+                    text,
+                    doc_comment,
+                    tags: DocTags::default(),
+                    span,
+                    allowed_lints: std::mem::take(allowed_lints),
+                });
+            }
         }
+        "var" => {
+            let mut name = String::new();
+            let mut value_type = None;
+            let mut assignment = None;
+            let mut setter = None;
+            let mut getter = None;
+            let block = parse_assignment(
+                filename,
+                span,
+                declaration,
+                &mut name,
+                &mut value_type,
+                &mut assignment,
+                &mut setter,
+                &mut getter,
+            )?;
 
-        let pos = pos.unwrap();
-        let export_type = match (open_paren, close_paren) {
-            (Some(open), Some(close)) if open < close && close < pos => {
-                let mut arg_iterator = line[open + 1..close]
-                    .split(',')
-                    .map(|x| x.trim().to_string());
-                let export_type = arg_iterator.next();
-                let options = arg_iterator.collect::<Vec<_>>();
-                Some((export_type.unwrap(), options))
+            let exported = annotations.iter().any(Annotation::is_export);
+            // Plain variables don't end the group of the exports around them
+            let section = exported.then(|| export_section(&mut frame.section, &name));
+
+            if (name.starts_with("_") && !settings.show_prefixed)
+                || !override_visibility.unwrap_or(true)
+            {
+                return Ok(None);
             }
-            (Some(x), Some(y)) if x > pos && y > pos => None,
-            (Some(x), None) if x > pos => None,
-            (None, Some(x)) if x > pos => None,
-            (None, None) => None,
-            _ => return Err(format!("Invalid syntax: {}", line)),
-        };
 
-        let mut name = String::new();
-        let mut value_type = None;
-        let mut assignment = None;
-        let mut setter = None;
-        let mut getter = None;
-        parse_assignment(
-            filename,
-            lineno,
-            &line[pos + 5..],
-            &mut name,
-            &mut value_type,
-            &mut assignment,
-            &mut setter,
-            &mut getter,
-        )?;
-
-        if (name.starts_with("_") && !settings.show_prefixed)
-            || !override_visibility.unwrap_or(true)
-        {
-            return Ok(None);
-        }
+            if block {
+                let entry_type = if exported {
+                    EntryType::EXPORT
+                } else {
+                    EntryType::VAR
+                };
+                frame.property_block = Some((entry_type, None));
+            }
 
-        let (export_type, options) = match export_type {
-            Some((x, y)) => (Some(x), y),
-            None => (None, Vec::new()),
-        };
+            if exported {
+                // The annotation giving the hint is replaced by it
+                let mut annotations = annotations;
+                let mut hint = None;
+                annotations.retain(|a| match parse_export_annotation(a) {
+                    Some(h) if hint.is_none() => {
+                        hint = Some(h);
+                        false
+                    }
+                    _ => true,
+                });
 
-        frame.exports.push(Symbol {
-            name: name,
-            arg: Some(SymbolArgs::ExportArgs(ExportArgStruct {
-                value_type: export_type.or(value_type),
-                options: options,
-                assignment: assignment,
-                setter: setter,
-                getter: getter,
-            })),
-            text: comment_buffer.drain(..).collect(),
-        });
-    } else if line.starts_with("enum") {
-        let pos = line.find('{');
-        if pos.is_none() {
-            return Err(format!("Invalid Syntax: {}", line));
+                let (text, doc_comment) = comment_buffer.take();
+                frame.exports.push(Symbol {
+                    name,
+                    arg: Some(SymbolArgs::ExportArgs(ExportArgStruct {
+                        value_type,
+                        assignment,
+                        hint,
+                        annotations,
+                        setter,
+                        getter,
+                        section: section.unwrap_or_default(),
+                    })),
+                    text,
+                    doc_comment,
+                    tags: DocTags::default(),
+                    span,
+                    allowed_lints: std::mem::take(allowed_lints),
+                });
+            } else {
+                let (text, doc_comment) = comment_buffer.take();
+                frame.variables.push(Symbol {
+                    name,
+                    arg: Some(SymbolArgs::VariableArgs(VariableArgStruct {
+                        value_type,
+                        assignment,
+                        annotations,
+                        setter,
+                        getter,
+                    })),
+                    text,
+                    doc_comment,
+                    tags: DocTags::default(),
+                    span,
+                    allowed_lints: std::mem::take(allowed_lints),
+                });
+            }
         }
+        "const" => {
+            let mut name = String::new();
+            let mut value_type = None;
+            let mut assignment = None;
+            let mut setter = None;
+            let mut getter = None;
+            parse_assignment(
+                filename,
+                span,
+                declaration,
+                &mut name,
+                &mut value_type,
+                &mut assignment,
+                &mut setter,
+                &mut getter,
+            )?;
 
-        let pos = pos.unwrap();
-        let enum_name = line[5..pos].trim().to_string();
-
-        if (enum_name.starts_with("_") && !settings.show_prefixed)
-            || !override_visibility.unwrap_or(true)
-        {
-            return Ok(None);
+            if (!name.starts_with("_") || settings.show_prefixed)
+                && override_visibility.unwrap_or(true)
+            {
+                let (text, doc_comment) = comment_buffer.take();
+                frame.constants.push(Symbol {
+                    name,
+                    arg: Some(SymbolArgs::VariableArgs(VariableArgStruct {
+                        value_type,
+                        assignment,
+                        annotations: Vec::new(),
+                        setter,
+                        getter,
+                    })),
+                    text,
+                    doc_comment,
+                    tags: DocTags::default(),
+                    span,
+                    allowed_lints: std::mem::take(allowed_lints),
+                });
+            }
         }
+        "export" => {
+            let pos = find_token(declaration, |t| t.is_keyword("var"))
+                .ok_or_else(|| syntax_error(filename, span, &statement.tokens))?;
+
+            let mut export_type = None;
+            let mut options = Vec::new();
+            if pos > 0 {
+                match bracket_contents(&declaration[..pos]) {
+                    Some((arguments, rest)) if declaration[0].is_symbol("(") && rest.is_empty() => {
+                        let mut arg_iterator =
+                            split_tokens(arguments, ",").into_iter().map(tokens_text);
+                        export_type = arg_iterator.next().filter(|x| !x.is_empty());
+                        options = arg_iterator.collect();
+                    }
+                    _ => return Err(syntax_error(filename, span, &statement.tokens)),
+                }
+            }
 
-        let mut enum_frame = EnumFrame::default();
-        let end = line.find('}');
-        let slice = match end {
-            Some(x) => &line[pos + 1..x],
-            None => &line[pos + 1..],
-        };
+            let mut name = String::new();
+            let mut value_type = None;
+            let mut assignment = None;
+            let mut setter = None;
+            let mut getter = None;
+            let block = parse_assignment(
+                filename,
+                span,
+                &declaration[pos + 1..],
+                &mut name,
+                &mut value_type,
+                &mut assignment,
+                &mut setter,
+                &mut getter,
+            )?;
 
-        parse_enum(
-            settings,
-            parsing_mode,
-            slice,
-            &mut enum_frame,
-            override_visibility,
-            comment_buffer,
-        )?;
+            if (name.starts_with("_") && !settings.show_prefixed)
+                || !override_visibility.unwrap_or(true)
+            {
+                return Ok(None);
+            }
 
-        if end.is_some() {
+            if block {
+                frame.property_block = Some((EntryType::EXPORT, None));
+            }
+            let (text, doc_comment) = comment_buffer.take();
+            frame.exports.push(Symbol {
+                name,
+                arg: Some(SymbolArgs::ExportArgs(ExportArgStruct {
+                    value_type: export_type.or(value_type),
+                    hint: parse_export_options(&options),
+                    assignment,
+                    annotations,
+                    setter,
+                    getter,
+                    // Godot 3 has no sections in the inspector
+                    section: InspectorSection::default(),
+                })),
+                text,
+                doc_comment,
+                tags: DocTags::default(),
+                span,
+                allowed_lints: std::mem::take(allowed_lints),
+            });
+        }
+        "enum" => {
+            let pos = find_token(declaration, |t| t.is_symbol("{"))
+                .ok_or_else(|| syntax_error(filename, span, &statement.tokens))?;
+            let enum_name = tokens_text(&declaration[..pos]);
+            let (values, _) = bracket_contents(&declaration[pos..])
+                .ok_or_else(|| syntax_error(filename, span, &statement.tokens))?;
+
+            let values = parse_enum(
+                filename,
+                settings,
+                frame,
+                parsing_mode,
+                statement,
+                values,
+                comment_buffer,
+                override_visibility,
+                allowed_lints,
+            )?;
+
+            if (enum_name.starts_with("_") && !settings.show_prefixed)
+                || !override_visibility.unwrap_or(true)
+            {
+                return Ok(None);
+            }
+
+            let (text, doc_comment) = comment_buffer.take();
             frame.enums.push(Symbol {
                 name: enum_name,
-                arg: Some(SymbolArgs::EnumArgs(enum_frame.values)),
-                text: comment_buffer.drain(..).collect(),
+                arg: Some(SymbolArgs::EnumArgs(values)),
+                text,
+                doc_comment,
+                tags: DocTags::default(),
+                span,
+                allowed_lints: std::mem::take(allowed_lints),
             });
-        } else {
-            return Ok(Some(Mode::Enum(enum_name, enum_frame)));
         }
+        _ => (),
     }
 
     Ok(None)
 }
 
-enum MatchType {
-    FAILURE,
-    MATCH,
-    FINISHED,
-}
-
-trait Predicate {
-    fn into_matcher(self) -> Box<dyn Matcher>;
-}
+// Applies an annotation starting a section of the inspector, returns whether it is one
+fn enter_section(section: &mut InspectorSection, annotation: &Annotation) -> bool {
+    let arguments = annotation
+        .arguments
+        .iter()
+        .map(|a| unquote(a))
+        .collect::<Vec<_>>();
+    let group = || match arguments.as_slice() {
+        [name, ..] if !name.is_empty() => Some(ExportGroup {
+            name: name.clone(),
+            prefix: arguments.get(1).cloned().unwrap_or_default(),
+        }),
+        // An empty name ends the group
+        _ => None,
+    };
 
-impl Predicate for char {
-    fn into_matcher(self) -> Box<dyn Matcher> {
-        Box::new(self)
+    match annotation.name.as_str() {
+        "export_category" => {
+            *section = InspectorSection {
+                category: arguments.first().cloned(),
+                ..InspectorSection::default()
+            }
+        }
+        "export_group" => {
+            section.group = group();
+            section.subgroup = None;
+        }
+        "export_subgroup" => section.subgroup = group(),
+        _ => return false,
     }
+
+    true
 }
 
-impl Predicate for &str {
-    fn into_matcher(self) -> Box<dyn Matcher> {
-        Box::new(StringMatcher {
-            index: 0,
-            chars: self.chars().collect(),
-            len: self.len(),
-        })
+// The section of an export, a group with a prefix ends at the first export without it
+fn export_section(section: &mut InspectorSection, name: &str) -> InspectorSection {
+    let outside =
+        |g: &Option<ExportGroup>| g.as_ref().is_some_and(|g| !name.starts_with(&g.prefix));
+    if outside(&section.group) {
+        section.group = None;
+        section.subgroup = None;
+    }
+    if outside(&section.subgroup) {
+        section.subgroup = None;
     }
-}
 
-trait Matcher {
-    fn matches(&mut self, c: char) -> MatchType;
+    section.clone()
 }
 
-struct StringMatcher {
-    index: usize,
-    len: usize,
-    chars: Vec<char>,
-}
+fn parse_annotations<'a, 'b>(
+    filename: &str,
+    tokens: &'b [Token<'a>],
+) -> Result<(Vec<Annotation>, &'b [Token<'a>]), Diagnostic> {
+    let mut annotations = Vec::new();
+    let mut rest = tokens;
+
+    while rest.first().is_some_and(|t| t.is_symbol("@")) {
+        let name = match rest.get(1) {
+            Some(name) if name.kind == TokenKind::Identifier => name.text.to_string(),
+            _ => {
+                return Err(Diagnostic::error(
+                    filename,
+                    SourceSpan::from_tokens(&rest[..1]),
+                    "expected annotation name".to_string(),
+                ))
+            }
+        };
+        rest = &rest[2..];
 
-impl Matcher for char {
-    fn matches(&mut self, c: char) -> MatchType {
-        if c == *self {
-            MatchType::FINISHED
-        } else {
-            MatchType::FAILURE
+        let mut arguments = Vec::new();
+        if rest.first().is_some_and(|t| t.is_symbol("(")) {
+            let (args, after) = bracket_contents(rest)
+                .ok_or_else(|| syntax_error(filename, SourceSpan::from_tokens(rest), rest))?;
+            arguments = split_tokens(args, ",")
+                .into_iter()
+                .filter(|a| !a.is_empty())
+                .map(tokens_text)
+                .collect();
+            rest = after;
         }
+
+        annotations.push(Annotation { name, arguments });
     }
+
+    Ok((annotations, rest))
 }
 
-impl Matcher for StringMatcher {
-    fn matches(&mut self, c: char) -> MatchType {
-        if self.index > self.len || self.chars[self.index] != c {
-            self.index = 0;
-            MatchType::FAILURE
-        } else if self.index == self.len - 1 && self.chars[self.index] == c {
-            MatchType::FINISHED
-        } else {
-            self.index += 1;
-            MatchType::MATCH
-        }
-    }
+fn unquote(s: &str) -> String {
+    s.strip_prefix('"')
+        .and_then(|s| s.strip_suffix('"'))
+        .or_else(|| s.strip_prefix('\'').and_then(|s| s.strip_suffix('\'')))
+        .unwrap_or(s)
+        .to_string()
 }
 
-fn find(
-    filename: &str,
-    lineno: u32,
-    s: &str,
-    p: impl Predicate,
-    parentheses: &mut Vec<char>,
-) -> Result<Option<usize>, String> {
-    let mut single_string = false;
-    let mut double_string = false;
-
-    let chars = s.chars().collect::<Vec<_>>();
-    let len = chars.len();
-
-    let mut matcher = p.into_matcher();
-    for i in 0..len {
-        if !single_string && !double_string {
-            let mut j = 0;
-            while i + j < len {
-                let c = chars[i + j];
-                j += 1;
-
-                match matcher.as_mut().matches(c) {
-                    MatchType::FAILURE => break,
-                    MatchType::FINISHED => return Ok(Some(i)),
-                    _ => (),
-                }
+fn is_quoted(s: &str) -> bool {
+    s.starts_with('"') || s.starts_with('\'')
+}
+
+// The hint of a Godot 3 export like "export(int, 1, 8)", given the options after the type
+fn parse_export_options(options: &[String]) -> Option<ExportHint> {
+    let strings = |options: &[String]| options.iter().map(|o| unquote(o)).collect::<Vec<_>>();
+    let is_number = |o: &String| o.parse::<f64>().is_ok();
+    let range = |numbers: &[String], options: Vec<String>| match numbers {
+        [max] => Some(ExportHint::Range {
+            min: "0".to_string(),
+            max: max.clone(),
+            step: None,
+            options,
+        }),
+        [min, max] | [min, max, _] => Some(ExportHint::Range {
+            min: min.clone(),
+            max: max.clone(),
+            step: numbers.get(2).cloned(),
+            options,
+        }),
+        _ => None,
+    };
+
+    let (first, rest) = options.split_first()?;
+    let hint = match first.as_str() {
+        "FLAGS" => ExportHint::Flags(strings(rest)),
+        "FILE" | "DIR" => {
+            let global = rest.first().is_some_and(|o| o == "GLOBAL");
+            let rest = if global { &rest[1..] } else { rest };
+            match first.as_str() {
+                "FILE" => ExportHint::File {
+                    filters: strings(rest),
+                    global,
+                },
+                _ => ExportHint::Dir { global },
             }
         }
+        "MULTILINE" => ExportHint::Multiline,
+        "EASE" => ExportHint::ExpEasing(Vec::new()),
+        "RGB" => ExportHint::ColorNoAlpha,
+        // The default for colors
+        "RGBA" => return None,
+        "EXP" if rest.iter().all(is_number) => range(rest, vec!["exp".to_string()])?,
+        x if x.starts_with("LAYERS_") => ExportHint::Layers(x["LAYERS_".len()..].to_lowercase()),
+        x if is_quoted(x) => ExportHint::Enum(strings(options)),
+        _ if options.iter().all(is_number) => {
+            range(options, Vec::new()).unwrap_or_else(|| ExportHint::Other(options.to_vec()))
+        }
+        _ => ExportHint::Other(options.to_vec()),
+    };
 
-        match chars[i] {
-            '"' if !single_string => double_string = true,
-            '\'' if !double_string => single_string = true,
-            x if x == '(' || x == '[' || x == '{' => parentheses.push(x),
-            ')' => match parentheses.pop() {
-                Some('(') => (),
-                Some(_) => return Err(format!("Failed to parse {}, line {}: Closing parentheses does not match opening parentheses", filename, lineno)),
-                None => return Err(format!("Failed to parse {}, line {}: extra ')'", filename, lineno))
-            }
-            ']' => match parentheses.pop() {
-                Some('[') => (),
-                Some(_) => return Err(format!("Failed to parse {}, line {}: Closing parentheses does not match opening parentheses", filename, lineno)),
-                None => return Err(format!("Failed to parse {}, line {}: extra ']'", filename, lineno))
-            }
-            '}' => match parentheses.pop() {
-                Some('{') => (),
-                Some(_) => return Err(format!("Failed to parse {}, line {}: Closing parentheses does not match opening parentheses", filename, lineno)),
-                None => return Err(format!("Failed to parse {}, line {}: extra '}}'", filename, lineno))
+    Some(hint)
+}
+
+// The hint of a Godot 4 export annotation like "@export_range(1, 8)"
+fn parse_export_annotation(annotation: &Annotation) -> Option<ExportHint> {
+    let arguments = annotation
+        .arguments
+        .iter()
+        .map(|a| unquote(a))
+        .collect::<Vec<_>>();
+
+    let hint = match annotation.name.as_str() {
+        "export_range" => match annotation.arguments.as_slice() {
+            [min, max, rest @ ..] => {
+                // The step is optional, the further hints are strings
+                let (step, options) = match rest.split_first() {
+                    Some((step, options)) if !is_quoted(step) => (Some(step.clone()), options),
+                    _ => (None, rest),
+                };
+                ExportHint::Range {
+                    min: min.clone(),
+                    max: max.clone(),
+                    step,
+                    options: options.iter().map(|o| unquote(o)).collect(),
+                }
             }
-            _ => (),
-        }
-    }
+            _ => return None,
+        },
+        "export_exp_easing" => ExportHint::ExpEasing(arguments),
+        "export_enum" => ExportHint::Enum(arguments),
+        "export_flags" => ExportHint::Flags(arguments),
+        "export_file" | "export_global_file" => ExportHint::File {
+            filters: arguments,
+            global: annotation.name == "export_global_file",
+        },
+        "export_dir" => ExportHint::Dir { global: false },
+        "export_global_dir" => ExportHint::Dir { global: true },
+        "export_multiline" => ExportHint::Multiline,
+        "export_placeholder" => ExportHint::Placeholder(arguments.join(", ")),
+        "export_color_no_alpha" => ExportHint::ColorNoAlpha,
+        "export_node_path" => ExportHint::NodePath(arguments),
+        name => ExportHint::Layers(name.strip_prefix("export_flags_")?.to_string()),
+    };
 
-    Ok(None)
+    Some(hint)
 }
 
+#[allow(clippy::too_many_arguments)]
 fn parse_assignment(
     filename: &str,
-    lineno: u32,
-    line: &str,
+    span: SourceSpan,
+    tokens: &[Token],
     name: &mut String,
     value_type: &mut Option<String>,
     assignment: &mut Option<String>,
     setter: &mut Option<String>,
     getter: &mut Option<String>,
-) -> Result<(), String> {
-    let assignment_pos = find(filename, lineno, line, '=', &mut Vec::new())?;
-    let type_pos = find(filename, lineno, line, ':', &mut Vec::new())?;
-    let setget_pos = find(filename, lineno, line, " setget ", &mut Vec::new())?;
-
-    match (assignment_pos, type_pos, setget_pos) {
-        (Some(apos), Some(tpos), Some(spos)) if tpos < apos && apos < spos => {
-            let setget = &line[spos + 7..]
-                .split(',')
-                .map(|x| x.trim())
-                .collect::<Vec<_>>();
-            match setget.as_slice() {
-                ["", get] => {
-                    getter.get_or_insert(get.to_string());
-                }
-                [set] | [set, ""] => {
-                    setter.get_or_insert(set.to_string());
-                }
-                [set, get] => {
-                    setter.get_or_insert(set.to_string());
-                    getter.get_or_insert(get.to_string());
-                }
-                _ => {
-                    return Err(format!(
-                        "Failed to parse {}, line {}: invalid syntax '{}'",
-                        filename, lineno, line
-                    ))
-                }
-            }
-            name.clone_from(&line[..tpos].trim().to_string());
-            value_type.get_or_insert(line[tpos + 1..apos].trim().to_string());
-            assignment.get_or_insert(line[apos + 1..spos].trim().to_string());
-        }
-        (Some(apos), Some(tpos), None) if tpos < apos => {
-            name.clone_from(&line[..tpos].trim().to_string());
-            value_type.get_or_insert(line[tpos + 1..apos].trim().to_string());
-            assignment.get_or_insert(line[apos + 1..].trim().to_string());
+) -> Result<bool, Diagnostic> {
+    let error = || syntax_error(filename, span, tokens);
+
+    let mut rest = match tokens.split_first() {
+        Some((first, rest)) if first.kind == TokenKind::Identifier => {
+            *name = first.text.to_string();
+            rest
         }
-        (Some(apos), None, Some(spos)) if apos < spos => {
-            let setget = &line[spos + 7..]
-                .split(',')
-                .map(|x| x.trim())
-                .collect::<Vec<_>>();
-            match setget.as_slice() {
-                ["", get] => {
-                    getter.get_or_insert(get.to_string());
-                }
-                [set] | [set, ""] => {
-                    setter.get_or_insert(set.to_string());
-                }
-                [set, get] => {
-                    setter.get_or_insert(set.to_string());
-                    getter.get_or_insert(get.to_string());
-                }
-                _ => {
-                    return Err(format!(
-                        "Failed to parse {}, line {}: invalid syntax '{}'",
-                        filename, lineno, line
-                    ))
+        _ => return Err(error()),
+    };
+
+    // A trailing colon starts the block of accessors of a Godot 4 property, unless it belongs
+    // to a lambda
+    let mut block = false;
+    if rest.last().is_some_and(|t| t.is_symbol(":")) {
+        rest = &rest[..rest.len() - 1];
+        block = find_token(rest, |t| t.is_keyword("func")).is_none();
+    }
+
+    // Godot 4 accessors may also follow on the same line, "var x: int: set = _set_x"
+    let accessors_pos = rest
+        .get(1..)
+        .and_then(|r| find_token(r, |t| t.is_symbol(":")))
+        .map(|pos| pos + 1)
+        .filter(|&pos| {
+            rest.get(pos + 1)
+                .is_some_and(|t| t.is_keyword("set") || t.is_keyword("get"))
+        });
+    if let Some(pos) = accessors_pos {
+        parse_accessors(filename, span, name, &rest[pos + 1..], setter, getter)?;
+        rest = &rest[..pos];
+    }
+
+    let setget_pos = find_token(rest, |t| t.is_keyword("setget"));
+    let (definition, setget) = match setget_pos {
+        Some(pos) => (&rest[..pos], Some(&rest[pos + 1..])),
+        None => (rest, None),
+    };
+
+    if let Some(first) = definition.first() {
+        let (type_tokens, value_tokens) = match first.text {
+            ":=" if first.kind == TokenKind::Symbol => {
+                (Some(&definition[..0]), Some(&definition[1..]))
+            }
+            ":" if first.kind == TokenKind::Symbol => {
+                match find_token(definition, |t| t.is_symbol("=")) {
+                    Some(pos) => (Some(&definition[1..pos]), Some(&definition[pos + 1..])),
+                    None => (Some(&definition[1..]), None),
                 }
             }
-            name.clone_from(&line[..apos].trim().to_string());
-            assignment.get_or_insert(line[apos + 1..spos].trim().to_string());
+            "=" if first.kind == TokenKind::Symbol => (None, Some(&definition[1..])),
+            _ => return Err(error()),
+        };
+
+        if let Some(type_tokens) = type_tokens {
+            value_type.get_or_insert(tokens_text(type_tokens));
         }
-        (Some(apos), None, None) => {
-            name.clone_from(&line[..apos].trim().to_string());
-            assignment.get_or_insert(line[apos + 1..].trim().to_string());
+        if let Some(value_tokens) = value_tokens {
+            assignment.get_or_insert(tokens_text(value_tokens));
         }
-        (None, Some(tpos), Some(spos)) if tpos < spos => {
-            let setget = &line[spos + 7..]
-                .split(',')
-                .map(|x| x.trim())
-                .collect::<Vec<_>>();
-            match setget.as_slice() {
-                ["", get] => {
-                    getter.get_or_insert(get.to_string());
-                }
-                [set] | [set, ""] => {
-                    setter.get_or_insert(set.to_string());
-                }
-                [set, get] => {
-                    setter.get_or_insert(set.to_string());
-                    getter.get_or_insert(get.to_string());
-                }
-                _ => {
-                    return Err(format!(
-                        "Failed to parse {}, line {}: invalid syntax '{}'",
-                        filename, lineno, line
-                    ))
-                }
+    }
+
+    if let Some(setget) = setget {
+        let setget = split_tokens(setget, ",")
+            .into_iter()
+            .map(tokens_text)
+            .collect::<Vec<_>>();
+        match setget
+            .iter()
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .as_slice()
+        {
+            ["", get] => {
+                getter.get_or_insert(get.to_string());
             }
-            name.clone_from(&line[..tpos].trim().to_string());
-            value_type.get_or_insert(line[tpos + 1..spos].trim().to_string());
-        }
-        (None, Some(tpos), None) => {
-            name.clone_from(&line[..tpos].trim().to_string());
-            value_type.get_or_insert(line[tpos + 1..].trim().to_string());
+            [set] | [set, ""] => {
+                setter.get_or_insert(set.to_string());
+            }
+            [set, get] => {
+                setter.get_or_insert(set.to_string());
+                getter.get_or_insert(get.to_string());
+            }
+            _ => return Err(error()),
         }
-        (None, None, Some(spos)) => {
-            let setget = &line[spos + 7..]
-                .split(',')
-                .map(|x| x.trim())
-                .collect::<Vec<_>>();
-            match setget.as_slice() {
-                ["", get] => {
-                    getter.get_or_insert(get.to_string());
-                }
-                [set] | [set, ""] => {
-                    setter.get_or_insert(set.to_string());
-                }
-                [set, get] => {
-                    setter.get_or_insert(set.to_string());
-                    getter.get_or_insert(get.to_string());
-                }
-                _ => {
-                    return Err(format!(
-                        "Failed to parse {}, line {}: invalid syntax '{}'",
-                        filename, lineno, line
-                    ))
+    }
+
+    Ok(block)
+}
+
+// Godot 4 accessors like "set = _set_x, get = _get_x". Accessors with an inline body, like
+// "set(value):", are named "@x_setter" the way Godot names them.
+fn parse_accessors(
+    filename: &str,
+    span: SourceSpan,
+    property: &str,
+    tokens: &[Token],
+    setter: &mut Option<String>,
+    getter: &mut Option<String>,
+) -> Result<(), Diagnostic> {
+    let error = || syntax_error(filename, span, tokens);
+
+    let mut rest = tokens;
+    while let Some(first) = rest.first() {
+        let (accessor, kind) = match first.text {
+            "set" if first.kind == TokenKind::Identifier => (&mut *setter, "setter"),
+            "get" if first.kind == TokenKind::Identifier => (&mut *getter, "getter"),
+            _ => return Err(error()),
+        };
+
+        match rest.get(1) {
+            Some(t) if t.is_symbol("=") => {
+                let end = find_token(rest, |t| t.is_symbol(",")).unwrap_or(rest.len());
+                match &rest[2..end] {
+                    [name] if name.kind == TokenKind::Identifier => {
+                        *accessor = Some(name.text.to_string())
+                    }
+                    _ => return Err(error()),
                 }
+                rest = rest.get(end + 1..).unwrap_or_default();
+            }
+            // The rest of the statement is the body of the accessor
+            Some(t) if t.is_symbol("(") || t.is_symbol(":") => {
+                *accessor = Some(format!("@{}_{}", property, kind));
+                break;
             }
-            name.clone_from(&line[..spos].trim().to_string());
+            _ => return Err(error()),
         }
-        (None, None, None) => {
-            name.clone_from(&line.trim().to_string());
+    }
+
+    Ok(())
+}
+
+fn parse_argument(
+    filename: &str,
+    span: SourceSpan,
+    tokens: &[Token],
+) -> Result<FunctionArgument, Diagnostic> {
+    let name = match tokens.first() {
+        Some(name) if name.kind == TokenKind::Identifier => name.text.to_string(),
+        _ => return Err(syntax_error(filename, span, tokens)),
+    };
+
+    let mut value_type = None;
+    let mut default_value = None;
+    match tokens.get(1) {
+        None => (),
+        Some(t) if t.is_symbol(":=") => {
+            value_type = Some(String::new());
+            default_value = Some(tokens_text(&tokens[2..]));
         }
-        _ => {
-            return Err(format!(
-                "Failed to parse {}, line {}: invalid syntax '{}'",
-                filename, lineno, line
-            ))
+        Some(t) if t.is_symbol(":") => match find_token(tokens, |t| t.is_symbol("=")) {
+            Some(pos) => {
+                value_type = Some(tokens_text(&tokens[2..pos]));
+                default_value = Some(tokens_text(&tokens[pos + 1..]));
+            }
+            None => value_type = Some(tokens_text(&tokens[2..])),
+        },
+        Some(t) if t.is_symbol("=") => default_value = Some(tokens_text(&tokens[2..])),
+        Some(_) => return Err(syntax_error(filename, span, tokens)),
+    }
+
+    Ok(FunctionArgument {
+        name,
+        value_type,
+        default_value,
+        span: SourceSpan::from_tokens(tokens),
+    })
+}
+
+// Signals may omit the parentheses when they have no arguments
+fn parse_signal(
+    filename: &str,
+    span: SourceSpan,
+    tokens: &[Token],
+    name: &mut String,
+    arguments: &mut Vec<FunctionArgument>,
+) -> Result<(), Diagnostic> {
+    let error = || syntax_error(filename, span, tokens);
+
+    match tokens.first() {
+        Some(t) if t.kind == TokenKind::Identifier => *name = t.text.to_string(),
+        _ => return Err(error()),
+    }
+    if tokens.len() == 1 {
+        return Ok(());
+    }
+    if !tokens[1].is_symbol("(") {
+        return Err(error());
+    }
+
+    let (args, rest) = bracket_contents(&tokens[1..]).ok_or_else(error)?;
+    if !rest.is_empty() {
+        return Err(error());
+    }
+    for argument in split_tokens(args, ",")
+        .into_iter()
+        .filter(|a| !a.is_empty())
+    {
+        let argument = parse_argument(filename, span, argument)?;
+        if argument.default_value.is_some() {
+            return Err(Diagnostic::error(
+                filename,
+                argument.span,
+                format!(
+                    "argument '{}' of a signal can't have a default value",
+                    argument.name
+                ),
+            ));
         }
-    };
+        arguments.push(argument);
+    }
 
     Ok(())
 }
 
 fn parse_function(
-    line: &str,
+    filename: &str,
+    span: SourceSpan,
+    tokens: &[Token],
     name: &mut String,
     arguments: &mut Vec<FunctionArgument>,
     super_arguments: &mut Option<Vec<FunctionArgument>>,
     return_type: &mut Option<String>,
-) -> Result<(), String> {
-    #[derive(PartialEq)]
-    enum SIDE {
-        Name,
-        Type,
-        Assignment,
-        Invalid,
+) -> Result<(), Diagnostic> {
+    let error = || syntax_error(filename, span, tokens);
+
+    match tokens.first() {
+        Some(t) if t.kind == TokenKind::Identifier => *name = t.text.to_string(),
+        _ => return Err(error()),
+    }
+    if !tokens.get(1).is_some_and(|t| t.is_symbol("(")) {
+        return Err(error());
     }
 
-    let mut finished = false;
+    let (args, mut rest) = bracket_contents(&tokens[1..]).ok_or_else(error)?;
+    for argument in split_tokens(args, ",")
+        .into_iter()
+        .filter(|a| !a.is_empty())
+    {
+        arguments.push(parse_argument(filename, span, argument)?);
+    }
 
-    let mut depth = 0;
-    let mut parentheses_count = 0;
-    let mut side = SIDE::Name;
-    let mut last_char = None;
-
-    let mut current_argument_name = String::new();
-    let mut current_argument_type = None;
-    let mut current_argument_assignment = None;
-    for c in line.chars() {
-        match c {
-            x if x.is_whitespace() => (),
-            _ if finished => return Err(format!("Invalid syntax: {}", line)),
-            '(' => {
-                if parentheses_count < 2 {
-                    depth += 1
-                } else {
-                    return Err(format!("Invalid syntax: {}", line));
-                }
-            }
-            ')' => {
-                depth -= 1;
-                if depth == 0 && !current_argument_name.is_empty() {
-                    match parentheses_count {
-                        0 => {
-                            arguments.push(FunctionArgument {
-                                name: current_argument_name,
-                                value_type: current_argument_type,
-                                default_value: current_argument_assignment,
-                            });
-                            current_argument_name = String::new();
-                            current_argument_type = None;
-                            current_argument_assignment = None;
-                        }
-                        1 => {
-                            super_arguments
-                                .get_or_insert(Vec::new())
-                                .push(FunctionArgument {
-                                    name: current_argument_name,
-                                    value_type: current_argument_type,
-                                    default_value: current_argument_assignment,
-                                });
-                            current_argument_name = String::new();
-                            current_argument_type = None;
-                            current_argument_assignment = None;
-                        }
-                        _ => return Err(format!("Invalid syntax: {}", line)),
-                    }
-                }
-                if depth == 0 {
-                    side = SIDE::Invalid;
-                    parentheses_count += 1;
-                }
-            }
-            '.' if depth == 0 && name == "_init" && parentheses_count == 1 => side = SIDE::Name,
-            '.' if depth == 0 => return Err(format!("Invalid syntax: {}", line)),
-            ':' if depth == 0 => finished = true,
-            ':' => {
-                side = SIDE::Type;
-                current_argument_type = Some(String::new());
-            }
-            ',' => {
-                match parentheses_count {
-                    0 => {
-                        arguments.push(FunctionArgument {
-                            name: current_argument_name,
-                            value_type: current_argument_type,
-                            default_value: current_argument_assignment,
-                        });
-                        current_argument_name = String::new();
-                        current_argument_type = None;
-                        current_argument_assignment = None;
-                    }
-                    1 => {
-                        super_arguments
-                            .get_or_insert(Vec::new())
-                            .push(FunctionArgument {
-                                name: current_argument_name,
-                                value_type: current_argument_type,
-                                default_value: current_argument_assignment,
-                            });
-                        current_argument_name = String::new();
-                        current_argument_type = None;
-                        current_argument_assignment = None;
-                    }
-                    _ => return Err(format!("Invalid syntax: {}", line)),
-                };
-            }
-            '-' if depth == 0 => (),
-            '>' => {
-                if last_char == Some('-') {
-                    side = SIDE::Type;
-                } else {
-                    return Err(format!("Invalid syntax: {}", line));
+    // Godot 3 passes arguments to the constructor of the base class via _init().(args)
+    if name == "_init" && rest.first().is_some_and(|t| t.is_symbol(".")) {
+        if !rest.get(1).is_some_and(|t| t.is_symbol("(")) {
+            return Err(error());
+        }
+        let (args, after) = bracket_contents(&rest[1..]).ok_or_else(error)?;
+        let super_arguments = super_arguments.get_or_insert(Vec::new());
+        for argument in split_tokens(args, ",")
+            .into_iter()
+            .filter(|a| !a.is_empty())
+        {
+            super_arguments.push(FunctionArgument {
+                name: tokens_text(argument),
+                value_type: None,
+                default_value: None,
+                span: SourceSpan::from_tokens(argument),
+            });
+        }
+        rest = after;
+    }
+
+    // Everything after the colon is the body of a one line function
+    let colon = find_token(rest, |t| t.is_symbol(":")).ok_or_else(error)?;
+    match rest.first() {
+        Some(t) if t.is_symbol("->") => {
+            return_type.get_or_insert(tokens_text(&rest[1..colon]));
+        }
+        _ if colon == 0 => (),
+        _ => return Err(error()),
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::parse_script;
+    use crate::diagnostic::Severity;
+
+    // The sections of the exports of a script, by name
+    fn export_sections(data: &DocumentationData) -> Vec<(&str, InspectorSection)> {
+        data.entries
+            .iter()
+            .filter(|e| e.entry_type == EntryType::EXPORT)
+            .flat_map(|e| &e.symbols)
+            .filter_map(|s| match &s.arg {
+                Some(SymbolArgs::ExportArgs(export)) => {
+                    Some((s.name.as_str(), export.section.clone()))
                 }
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn group(name: &str, prefix: &str) -> Option<ExportGroup> {
+        Some(ExportGroup {
+            name: name.to_string(),
+            prefix: prefix.to_string(),
+        })
+    }
+
+    #[test]
+    fn plain_variables_keep_prefixed_group() {
+        let source = "@export_group(\"Jump\", \"jump_\")\n\
+                      @export var jump_height = 1\n\
+                      var helper = 0\n\
+                      @export var jump_speed = 2\n\
+                      @export var other = 3\n";
+        let (data, diagnostics) = parse_script(source);
+        assert!(diagnostics.is_empty());
+
+        let jump = InspectorSection {
+            group: group("Jump", "jump_"),
+            ..InspectorSection::default()
+        };
+        assert_eq!(
+            export_sections(&data),
+            vec![
+                ("other", InspectorSection::default()),
+                ("jump_height", jump.clone()),
+                ("jump_speed", jump),
+            ]
+        );
+    }
+
+    #[test]
+    fn ungrouped_exports_precede_groups() {
+        let source = "@export var a = 0\n\
+                      @export_category(\"Movement\")\n\
+                      @export_group(\"Jump\")\n\
+                      @export_subgroup(\"Double\")\n\
+                      @export var b = 0\n\
+                      @export_subgroup(\"\")\n\
+                      @export var c = 0\n\
+                      @export_group(\"\")\n\
+                      @export var d = 0\n";
+        let (data, diagnostics) = parse_script(source);
+        assert!(diagnostics.is_empty());
+
+        let names = export_sections(&data)
+            .into_iter()
+            .map(|(name, _)| name.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["a", "d", "c", "b"]);
+    }
+
+    #[test]
+    fn section_headings() {
+        let movement = InspectorSection {
+            category: Some("Movement".to_string()),
+            ..InspectorSection::default()
+        };
+        let jump = InspectorSection {
+            group: group("Jump", ""),
+            ..movement.clone()
+        };
+        let dash = InspectorSection {
+            group: group("Dash", ""),
+            ..movement.clone()
+        };
+
+        let none = InspectorSection::default();
+        assert_eq!(none.headings(&none), vec![]);
+        assert_eq!(jump.headings(&none), vec![(0, "Movement"), (1, "Jump")]);
+        assert_eq!(dash.headings(&jump), vec![(1, "Dash")]);
+        assert_eq!(jump.headings(&jump), vec![]);
+        // The enclosing section is repeated when a group ends
+        assert_eq!(movement.headings(&jump), vec![(0, "Movement")]);
+    }
+
+    // Names of the symbols of the given type
+    fn names(entries: &[DocumentationEntry], entry_type: EntryType) -> Vec<&str> {
+        entries
+            .iter()
+            .filter(|e| e.entry_type == entry_type)
+            .flat_map(|e| &e.symbols)
+            .map(|s| s.name.as_str())
+            .collect()
+    }
+
+    fn symbol<'a>(data: &'a DocumentationData, name: &str) -> &'a Symbol {
+        data.entries
+            .iter()
+            .flat_map(|e| &e.symbols)
+            .find(|s| s.name == name)
+            .unwrap()
+    }
+
+    fn export_hint(data: &DocumentationData, name: &str) -> String {
+        match &symbol(data, name).arg {
+            Some(SymbolArgs::ExportArgs(export)) => export.hint.as_ref().unwrap().to_string(),
+            _ => panic!("{} is not an export", name),
+        }
+    }
+
+    fn accessors<'a>(
+        data: &'a DocumentationData,
+        name: &str,
+    ) -> (Option<&'a str>, Option<&'a str>) {
+        match &symbol(data, name).arg {
+            Some(SymbolArgs::VariableArgs(VariableArgStruct { setter, getter, .. }))
+            | Some(SymbolArgs::ExportArgs(ExportArgStruct { setter, getter, .. })) => {
+                (setter.as_deref(), getter.as_deref())
             }
-            '=' if depth == 1 && side != SIDE::Assignment => side = SIDE::Assignment,
-            x if depth == 0 && side == SIDE::Name => name.push(x),
-            x if depth == 0 && side == SIDE::Type => {
-                return_type.get_or_insert(String::new()).push(x)
-            }
-            x if side == SIDE::Name => current_argument_name.push(x),
-            x if side == SIDE::Type => {
-                current_argument_type = current_argument_type.map(|mut s| {
-                    s.push(x);
-                    s
-                })
-            }
-            x if side == SIDE::Assignment => current_argument_assignment
-                .get_or_insert(String::new())
-                .push(x),
-            _ if side == SIDE::Invalid => return Err(format!("Invalid syntax: {}", line)),
-            _ => panic!("parse_function: Some case not covered"),
+            _ => panic!("{} is not a variable", name),
+        }
+    }
+
+    #[test]
+    fn script_headers() {
+        let (data, diagnostics) =
+            parse_script("@tool\nclass_name Player, \"res://icon.png\"\nextends \"base.gd\"\n");
+        assert!(diagnostics.is_empty());
+        assert!(data.tool);
+        assert_eq!(data.class_name.as_deref(), Some("Player"));
+        assert_eq!(data.extends.as_deref(), Some("\"base.gd\""));
+
+        let (data, _) = parse_script("class_name Player extends Node\n");
+        assert_eq!(data.class_name.as_deref(), Some("Player"));
+        assert_eq!(data.extends.as_deref(), Some("Node"));
+        assert!(!data.tool);
+    }
+
+    #[test]
+    fn annotations() {
+        let source = "@onready var a = 1\n\
+                      @export_range(0, 10, 0.5) var b := 2.0\n\
+                      @export\n\
+                      var c: int\n";
+        let (data, diagnostics) = parse_script(source);
+        assert!(diagnostics.is_empty());
+        assert_eq!(names(&data.entries, EntryType::VAR), ["a"]);
+        assert_eq!(names(&data.entries, EntryType::EXPORT), ["b", "c"]);
+
+        let Some(SymbolArgs::VariableArgs(a)) = &symbol(&data, "a").arg else {
+            panic!("not a variable");
+        };
+        assert_eq!(a.annotations[0].to_string(), "@onready");
+        assert_eq!(a.assignment.as_deref(), Some("1"));
+        let Some(SymbolArgs::ExportArgs(c)) = &symbol(&data, "c").arg else {
+            panic!("not an export");
         };
-        last_char = Some(c);
+        assert_eq!(c.annotations[0].to_string(), "@export");
+        assert_eq!(c.value_type.as_deref(), Some("int"));
+        assert!(c.hint.is_none());
     }
 
-    Ok(())
+    #[test]
+    fn export_hints() {
+        let source = "export(float, 0, 10, 0.5) var a\n\
+                      @export_range(0, 10, 0.5) var b: float\n\
+                      @export_enum(\"Warrior\", \"Magician\") var c: int\n\
+                      export(String, FILE, \"*.txt\") var d\n\
+                      @export_file(\"*.txt\") var e: String\n\
+                      @export_flags_2d_physics var f: int\n\
+                      @export_node_path(\"Button\") var g: NodePath\n";
+        let (data, diagnostics) = parse_script(source);
+        assert!(diagnostics.is_empty());
+        assert_eq!(export_hint(&data, "a"), "Range 0 to 10, step 0.5");
+        assert_eq!(export_hint(&data, "b"), "Range 0 to 10, step 0.5");
+        assert_eq!(export_hint(&data, "c"), "One of Warrior, Magician");
+        assert_eq!(export_hint(&data, "d"), "File (*.txt)");
+        assert_eq!(export_hint(&data, "e"), "File (*.txt)");
+        assert_eq!(export_hint(&data, "f"), "2D physics layers");
+        assert_eq!(export_hint(&data, "g"), "Node path to Button");
+    }
+
+    #[test]
+    fn property_accessors() {
+        let source = "var a: int: set = _set_a, get = _get_a\n\
+                      var b setget set_b, get_b\n\
+                      var c:\n\
+                      \tset(value):\n\
+                      \t\tpass\n\
+                      \tget:\n\
+                      \t\treturn 1\n\
+                      var d\n";
+        let (data, diagnostics) = parse_script(source);
+        assert!(diagnostics.is_empty());
+        assert_eq!(accessors(&data, "a"), (Some("_set_a"), Some("_get_a")));
+        assert_eq!(accessors(&data, "b"), (Some("set_b"), Some("get_b")));
+        assert_eq!(
+            accessors(&data, "c"),
+            (Some("@c_setter"), Some("@c_getter"))
+        );
+        assert_eq!(accessors(&data, "d"), (None, None));
+    }
+
+    #[test]
+    fn signal_parameters() {
+        let (data, diagnostics) =
+            parse_script("signal hit(damage: int, source)\nsignal done\nsignal bad(x = 1)\n");
+        let Some(SymbolArgs::SignalArgs(arguments)) = &symbol(&data, "hit").arg else {
+            panic!("not a signal");
+        };
+        let arguments = arguments.iter().map(|a| a.to_string()).collect::<Vec<_>>();
+        assert_eq!(arguments, ["damage: int", "source"]);
+        assert!(matches!(
+            &symbol(&data, "done").arg,
+            Some(SymbolArgs::SignalArgs(arguments)) if arguments.is_empty()
+        ));
+
+        // Like in Godot, signals have no default values
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].span.line, 3);
+    }
+
+    #[test]
+    fn doc_tags() {
+        let source = "# Moves\n\
+                      # @param speed How\n\
+                      #   fast\n\
+                      # @return Whether it moved\n\
+                      # @param nope Not an argument\n\
+                      # @experimental\n\
+                      func move(speed):\n\
+                      \tpass\n";
+        let (data, diagnostics) = parse_script(source);
+        let tags = &symbol(&data, "move").tags;
+        assert_eq!(symbol(&data, "move").text, ["Moves"]);
+        assert_eq!(tags.params[0].name, "speed");
+        assert_eq!(tags.params[0].text, "How fast");
+        assert_eq!(tags.returns.as_deref(), Some("Whether it moved"));
+        assert_eq!(tags.experimental.as_deref(), Some(""));
+        assert!(tags.deprecated.is_none());
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].message,
+            "@param 'nope' of function 'move' does not name an argument"
+        );
+    }
+
+    fn lines(text: &str) -> Vec<String> {
+        text.lines().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn unknown_doc_tags() {
+        let mut text =
+            lines("Spawns an enemy\n@tutorial https://a.b\nat the spawn point\n@since 2.0");
+        let tags = parse_doc_tags(&mut text);
+        // Unknown tags are part of the description, and so are the lines following them
+        assert_eq!(
+            text,
+            [
+                "Spawns an enemy",
+                "@tutorial https://a.b",
+                "at the spawn point"
+            ]
+        );
+        assert_eq!(tags.since.as_deref(), Some("2.0"));
+        assert!(tags.params.is_empty());
+    }
+
+    #[test]
+    fn multi_line_params() {
+        let mut text = lines(
+            "Moves\n@param speed How fast,\n  in pixels\n\n  per second\n@param dir\nWhere to",
+        );
+        let tags = parse_doc_tags(&mut text);
+        assert_eq!(text, ["Moves"]);
+        let params = tags
+            .params
+            .iter()
+            .map(|p| (p.name.as_str(), p.text.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            params,
+            [
+                ("speed", "How fast, in pixels per second"),
+                ("dir", "Where to")
+            ]
+        );
+    }
+
+    #[test]
+    fn source_spans() {
+        let (data, _) = parse_script("var a\n\nfunc  f(x: int):\n\tpass\n");
+        let f = symbol(&data, "f");
+        assert_eq!((f.span.line, f.span.column), (3, 1));
+        let Some(SymbolArgs::FunctionArgs(function)) = &f.arg else {
+            panic!("not a function");
+        };
+        let x = function.arguments[0].span;
+        assert_eq!((x.line, x.column, x.end_line, x.end_column), (3, 9, 3, 15));
+    }
+
+    #[test]
+    fn statements_after_errors() {
+        let (data, diagnostics) = parse_script("var = 3\nvar ok\n");
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+        assert_eq!(diagnostics[0].span.line, 1);
+        assert_eq!(names(&data.entries, EntryType::VAR), ["ok"]);
+    }
+
+    #[test]
+    fn inner_classes_with_function_bodies() {
+        let source = "class Inner:\n\
+                      \tfunc f():\n\
+                      \t\tpass\n\
+                      \tfunc g():\n\
+                      \t\tif true:\n\
+                      \t\t\tpass\n\
+                      var after\n";
+        let (data, diagnostics) = parse_script(source);
+        assert!(diagnostics.is_empty());
+
+        assert_eq!(names(&data.entries, EntryType::CLASS), ["Inner"]);
+        assert_eq!(names(&data.entries, EntryType::VAR), ["after"]);
+        let inner = &data.entries[0].symbols[0];
+        let Some(SymbolArgs::ClassArgs(class)) = &inner.arg else {
+            panic!("not a class");
+        };
+        assert_eq!(names(&class.entries, EntryType::FUNC), ["f", "g"]);
+    }
+
+    #[test]
+    fn arguments_after_typed_arguments() {
+        let (data, diagnostics) =
+            parse_script("func f(a: int, b, c := 1, d: float = 2.0):\n\tpass\n");
+        assert!(diagnostics.is_empty());
+
+        let Some(SymbolArgs::FunctionArgs(function)) = &data.entries[0].symbols[0].arg else {
+            panic!("not a function");
+        };
+        let arguments = function
+            .arguments
+            .iter()
+            .map(|a| {
+                (
+                    a.name.as_str(),
+                    a.value_type.as_deref(),
+                    a.default_value.as_deref(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            arguments,
+            [
+                ("a", Some("int"), None),
+                ("b", None, None),
+                ("c", Some(""), Some("1")),
+                ("d", Some("float"), Some("2.0")),
+            ]
+        );
+    }
+
+    #[test]
+    fn truncated_input_is_reported() {
+        let sources = [
+            "@export_range(0, 10",
+            "func f(a,",
+            "enum {",
+            "signal s(",
+            "var x = [1,",
+            "var s = \"abc",
+            "var x:\n\tset(",
+            "func f() ->",
+            "@",
+        ];
+        for source in sources {
+            let (_, diagnostics) = parse_script(source);
+            assert!(
+                diagnostics.iter().any(|d| d.severity == Severity::Error),
+                "no error for {:?}",
+                source
+            );
+        }
+    }
+
+    #[test]
+    fn script_header_comments() {
+        let source = "## The player.\n\
+                      @tool\n\
+                      ## Moves around.\n\
+                      ## @since 1.1\n\
+                      extends Node\n\
+                      # Speed in pixels\n\
+                      var speed = 1\n";
+        let (data, _) = parse_script(source);
+        assert!(data.tool);
+        assert!(data.doc_comment);
+        assert_eq!(data.text, ["The player.", "Moves around."]);
+        assert_eq!(data.tags.since.as_deref(), Some("1.1"));
+        assert_eq!(data.entries[0].symbols[0].text, ["Speed in pixels"]);
+
+        let (data, _) = parse_script("extends Node\n## Speed\nvar speed = 1\n");
+        assert!(data.text.is_empty());
+    }
+
+    #[test]
+    fn uneven_indentation() {
+        let source = "func f():\n  if true:\n     pass\n  pass\n";
+        let (_, diagnostics) = parse_script(source);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+        assert_eq!(diagnostics[0].span.line, 3);
+        assert_eq!(diagnostics[0].notes[0].span.line, 2);
+
+        let (_, diagnostics) = parse_script("func f():\n\tif true:\n\t\tpass\n");
+        assert!(diagnostics.is_empty());
+    }
 }
@@ -0,0 +1,824 @@
+use crate::parser::{
+    DocTags, DocumentationData, DocumentationEntry, EntryType, InheritedEntry, InheritedMembers,
+    Symbol, SymbolArgs,
+};
+
+use std::collections::{HashMap, HashSet};
+use std::ffi::OsStr;
+use std::fmt::{Display, Formatter};
+use std::path::{Component, Path, PathBuf};
+
+// Engine classes along with the class they inherit from, covering the classes declaring
+// callbacks and the ones scripts commonly extend, for Godot 3 and 4
+const ENGINE_CLASSES: &[(&str, &str)] = &[
+    ("RefCounted", "Object"),
+    ("Reference", "Object"),
+    ("Resource", "RefCounted"),
+    ("EditorScript", "RefCounted"),
+    ("MainLoop", "Object"),
+    ("SceneTree", "MainLoop"),
+    ("Node", "Object"),
+    ("CanvasItem", "Node"),
+    ("CanvasLayer", "Node"),
+    ("Timer", "Node"),
+    ("EditorPlugin", "Node"),
+    ("Node2D", "CanvasItem"),
+    ("Sprite2D", "Node2D"),
+    ("Sprite", "Node2D"),
+    ("AnimatedSprite2D", "Node2D"),
+    ("Camera2D", "Node2D"),
+    ("CollisionObject2D", "Node2D"),
+    ("Area2D", "CollisionObject2D"),
+    ("PhysicsBody2D", "CollisionObject2D"),
+    ("StaticBody2D", "PhysicsBody2D"),
+    ("RigidBody2D", "PhysicsBody2D"),
+    ("CharacterBody2D", "PhysicsBody2D"),
+    ("KinematicBody2D", "PhysicsBody2D"),
+    ("Node3D", "Node"),
+    ("Spatial", "Node"),
+    ("CollisionObject3D", "Node3D"),
+    ("Area3D", "CollisionObject3D"),
+    ("PhysicsBody3D", "CollisionObject3D"),
+    ("StaticBody3D", "PhysicsBody3D"),
+    ("RigidBody3D", "PhysicsBody3D"),
+    ("CharacterBody3D", "PhysicsBody3D"),
+    ("CollisionObject", "Spatial"),
+    ("PhysicsBody", "CollisionObject"),
+    ("RigidBody", "PhysicsBody"),
+    ("KinematicBody", "PhysicsBody"),
+    ("Control", "CanvasItem"),
+    ("Container", "Control"),
+    ("BoxContainer", "Container"),
+    ("HBoxContainer", "BoxContainer"),
+    ("VBoxContainer", "BoxContainer"),
+    ("Panel", "Control"),
+    ("Label", "Control"),
+    ("TextureRect", "Control"),
+    ("BaseButton", "Control"),
+    ("Button", "BaseButton"),
+];
+
+// Callbacks of the engine by the class declaring them, scripts implement them by defining a
+// method of the same name
+const ENGINE_VIRTUALS: &[(&str, &[&str])] = &[
+    (
+        "Object",
+        &[
+            "_init",
+            "_notification",
+            "_get",
+            "_set",
+            "_get_property_list",
+            "_validate_property",
+            "_property_can_revert",
+            "_property_get_revert",
+            "_to_string",
+        ],
+    ),
+    (
+        "Node",
+        &[
+            "_ready",
+            "_enter_tree",
+            "_exit_tree",
+            "_process",
+            "_physics_process",
+            "_input",
+            "_unhandled_input",
+            "_unhandled_key_input",
+            "_shortcut_input",
+            "_get_configuration_warning",
+            "_get_configuration_warnings",
+        ],
+    ),
+    ("CanvasItem", &["_draw"]),
+    (
+        "Control",
+        &[
+            "_gui_input",
+            "_can_drop_data",
+            "_drop_data",
+            "_get_drag_data",
+            "_make_custom_tooltip",
+            "_has_point",
+            "_get_minimum_size",
+            "_clips_input",
+        ],
+    ),
+    ("RigidBody2D", &["_integrate_forces"]),
+    ("RigidBody3D", &["_integrate_forces"]),
+    ("RigidBody", &["_integrate_forces"]),
+    ("EditorScript", &["_run"]),
+];
+
+// Whether the method implements a callback of the engine class or one of its ancestors.
+// Classes missing from ENGINE_CLASSES may inherit any of the callbacks.
+fn is_engine_virtual(engine_class: &str, name: &str) -> bool {
+    let mut class = engine_class;
+    loop {
+        let virtuals = ENGINE_VIRTUALS.iter().find(|(c, _)| *c == class);
+        if virtuals.is_some_and(|(_, virtuals)| virtuals.contains(&name)) {
+            return true;
+        }
+        if class == "Object" {
+            return false;
+        }
+
+        match ENGINE_CLASSES.iter().find(|(c, _)| *c == class) {
+            Some((_, parent)) => class = parent,
+            None => return ENGINE_VIRTUALS.iter().any(|(_, v)| v.contains(&name)),
+        }
+    }
+}
+
+pub struct ProjectFile {
+    pub path: PathBuf,
+    pub resource_path: String,
+    pub data: DocumentationData,
+}
+
+impl ProjectFile {
+    pub fn new(path: PathBuf, resource_prefix: &str, data: DocumentationData) -> ProjectFile {
+        let mut resource_path = resource_prefix.to_string();
+        for component in path.components() {
+            if let Component::Normal(c) = component {
+                if !resource_path.ends_with('/') {
+                    resource_path.push('/');
+                }
+                resource_path += &c.to_string_lossy();
+            }
+        }
+
+        ProjectFile {
+            path,
+            resource_path,
+            data,
+        }
+    }
+
+    fn display_name(&self) -> String {
+        self.data
+            .class_name
+            .clone()
+            .unwrap_or_else(|| format!("\"{}\"", self.resource_path))
+    }
+}
+
+/// Returns the "res://" path of the given input directory, by searching for the
+/// enclosing project.godot. If there is none, the input directory is treated as the project root.
+pub fn resource_prefix(input_dir: &Path) -> String {
+    let mut prefix = "res://".to_string();
+
+    if let Ok(input_dir) = input_dir.canonicalize() {
+        for root in input_dir.ancestors() {
+            if root.join("project.godot").is_file() {
+                for component in input_dir.strip_prefix(root).unwrap().components() {
+                    prefix += &component.as_os_str().to_string_lossy();
+                    prefix.push('/');
+                }
+                break;
+            }
+        }
+    }
+
+    prefix
+}
+
+/// Returns the path of the input directory inside of the enclosing git repository, along with
+/// the commit checked out there. Returns None if the input directory isn't part of a repository.
+pub fn git_checkout(input_dir: &Path) -> Option<(String, Option<String>)> {
+    let input_dir = input_dir.canonicalize().ok()?;
+    let root = input_dir
+        .ancestors()
+        .find(|root| root.join(".git").exists())?;
+
+    let mut prefix = String::new();
+    for component in input_dir.strip_prefix(root).unwrap().components() {
+        prefix += &component.as_os_str().to_string_lossy();
+        prefix.push('/');
+    }
+
+    Some((prefix, git_head(&root.join(".git"))))
+}
+
+fn git_head(git_dir: &Path) -> Option<String> {
+    // Worktrees and submodules have a file pointing to the actual git directory
+    let git_dir = if git_dir.is_file() {
+        let link = std::fs::read_to_string(git_dir).ok()?;
+        git_dir.parent()?.join(link.strip_prefix("gitdir:")?.trim())
+    } else {
+        git_dir.to_path_buf()
+    };
+
+    let head = std::fs::read_to_string(git_dir.join("HEAD")).ok()?;
+    let reference = match head.trim().strip_prefix("ref:") {
+        Some(reference) => reference.trim(),
+        // Detached HEAD
+        None => return Some(head.trim().to_string()),
+    };
+
+    // The branches of worktrees are stored in the git directory they share
+    let common_dir = match std::fs::read_to_string(git_dir.join("commondir")) {
+        Ok(dir) => git_dir.join(dir.trim()),
+        Err(_) => git_dir,
+    };
+    if let Ok(rev) = std::fs::read_to_string(common_dir.join(reference)) {
+        return Some(rev.trim().to_string());
+    }
+
+    let packed_refs = std::fs::read_to_string(common_dir.join("packed-refs")).ok()?;
+    packed_refs
+        .lines()
+        .filter_map(|line| line.split_once(' '))
+        .find(|(_, name)| *name == reference)
+        .map(|(rev, _)| rev.to_string())
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Version {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+}
+
+impl Display for Version {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+// Parses versions like "1.2.3", "v1.2" or "2.0.0-beta", missing parts are 0
+fn parse_version(s: &str) -> Option<Version> {
+    let s = s.trim().trim_start_matches('v');
+    let s = s.split(['-', '+']).next().unwrap();
+    let mut parts = s.split('.').map(|p| p.parse::<u64>());
+
+    let major = parts.next()?.ok()?;
+    let minor = parts.next().unwrap_or(Ok(0)).ok()?;
+    let patch = parts.next().unwrap_or(Ok(0)).ok()?;
+    if parts.next().is_some() {
+        return None;
+    }
+
+    Some(Version {
+        major,
+        minor,
+        patch,
+    })
+}
+
+fn find_plugin_configs(dir: &Path, configs: &mut Vec<PathBuf>) {
+    if let Ok(entries) = std::fs::read_dir(dir) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                find_plugin_configs(&path, configs);
+            } else if path.file_name() == Some(OsStr::new("plugin.cfg")) {
+                configs.push(path);
+            }
+        }
+    }
+}
+
+/// Returns the version of the addon in the given directory, read from its plugin.cfg.
+/// Returns None if there is no plugin.cfg, or several of them in different subdirectories.
+pub fn plugin_version(dir: &Path) -> Result<Option<Version>, String> {
+    let mut configs = Vec::new();
+    if dir.join("plugin.cfg").is_file() {
+        configs.push(dir.join("plugin.cfg"));
+    } else {
+        find_plugin_configs(dir, &mut configs);
+    }
+    let config = match configs.as_slice() {
+        [config] => config,
+        _ => return Ok(None),
+    };
+
+    let content = std::fs::read_to_string(config)
+        .map_err(|e| format!("Failed to read {}: {}", config.display(), e))?;
+    let mut section = "";
+    for line in content.lines().map(str::trim) {
+        if line.starts_with('[') {
+            section = line;
+            continue;
+        }
+
+        match line.split_once('=') {
+            Some((key, value)) if section == "[plugin]" && key.trim() == "version" => {
+                let value = value.trim().trim_matches('"');
+                return parse_version(value)
+                    .map(Some)
+                    .ok_or_else(|| format!("{}: invalid version '{}'", config.display(), value));
+            }
+            _ => (),
+        }
+    }
+
+    Ok(None)
+}
+
+fn normalize_resource_path(path: &str) -> String {
+    let mut components: Vec<&str> = Vec::new();
+    for component in path.split('/') {
+        match component {
+            "" | "." => (),
+            ".." => {
+                components.pop();
+            }
+            x => components.push(x),
+        }
+    }
+
+    format!("res://{}", components.join("/"))
+}
+
+fn resolve_extends(
+    extends: &str,
+    resource_path: &str,
+    class_names: &HashMap<String, usize>,
+    resource_paths: &HashMap<String, usize>,
+) -> Option<usize> {
+    let quote = extends.chars().next()?;
+    if quote == '"' || quote == '\'' {
+        // Inner classes of other scripts ("res://x.gd".Inner) are not resolved
+        let path = extends[1..].strip_suffix(quote)?;
+        if path.contains(quote) {
+            return None;
+        }
+
+        let path = match path.strip_prefix("res://") {
+            Some(path) => normalize_resource_path(path),
+            None => {
+                let relative = resource_path.strip_prefix("res://")?;
+                let dir = &relative[..relative.rfind('/').unwrap_or(0)];
+                normalize_resource_path(&format!("{}/{}", dir, path))
+            }
+        };
+
+        resource_paths.get(path.as_str()).copied()
+    } else {
+        class_names.get(extends).copied()
+    }
+}
+
+fn member_names(entries: &[DocumentationEntry]) -> Vec<InheritedEntry> {
+    entries
+        .iter()
+        .map(|entry| InheritedEntry {
+            entry_type: entry.entry_type,
+            names: entry.symbols.iter().map(|s| s.name.clone()).collect(),
+        })
+        .collect()
+}
+
+// The description of a function, whether it is a doc comment, and its tags
+type FunctionDocs = (Vec<String>, bool, DocTags);
+
+fn function_docs(entries: &[DocumentationEntry]) -> HashMap<String, FunctionDocs> {
+    entries
+        .iter()
+        .filter(|e| e.entry_type == EntryType::FUNC)
+        .flat_map(|e| e.symbols.iter())
+        .map(|s| {
+            let docs = (s.text.clone(), s.doc_comment, s.tags.clone());
+            (s.name.clone(), docs)
+        })
+        .collect()
+}
+
+// The scripts of the project by their index, as they were before resolving their inheritance
+struct Scripts {
+    class_names: HashMap<String, usize>,
+    resource_paths: HashMap<String, usize>,
+    names: Vec<String>,
+    extends: Vec<Option<String>>,
+    parents: Vec<Option<usize>>,
+    members: Vec<Vec<InheritedEntry>>,
+    // All functions of a script, including the hidden ones, which are overridden all the same
+    declared_functions: Vec<HashSet<String>>,
+    // Documentation of the listed functions, which overrides inherit
+    functions: Vec<HashMap<String, FunctionDocs>>,
+}
+
+impl Scripts {
+    fn resolve(&self, extends: &Option<String>, resource_path: &str) -> Option<usize> {
+        extends.as_ref().and_then(|e| {
+            resolve_extends(e, resource_path, &self.class_names, &self.resource_paths)
+        })
+    }
+
+    // The engine class at the root of the project scripts a class extends, classes without an
+    // extends clause extend RefCounted
+    fn engine_class(&self, extends: &Option<String>, parent: Option<usize>) -> String {
+        let mut extends = extends;
+        let mut ancestor = parent;
+        let mut visited = HashSet::new();
+        while let Some(p) = ancestor {
+            if !visited.insert(p) {
+                break;
+            }
+            extends = &self.extends[p];
+            ancestor = self.parents[p];
+        }
+
+        extends.clone().unwrap_or_else(|| "RefCounted".to_string())
+    }
+}
+
+fn mark_overrides(
+    scripts: &Scripts,
+    parent: Option<usize>,
+    engine_class: &str,
+    entries: &mut [DocumentationEntry],
+) {
+    let symbols = entries
+        .iter_mut()
+        .filter(|e| e.entry_type == EntryType::FUNC)
+        .flat_map(|e| e.symbols.iter_mut());
+
+    for Symbol {
+        name,
+        arg,
+        text,
+        doc_comment,
+        tags,
+        ..
+    } in symbols
+    {
+        let function = match arg {
+            Some(SymbolArgs::FunctionArgs(function)) => function,
+            _ => continue,
+        };
+        function.engine_virtual = is_engine_virtual(engine_class, name);
+
+        let mut visited = HashSet::new();
+        let mut ancestor = parent;
+        while let Some(p) = ancestor {
+            if !visited.insert(p) {
+                break;
+            }
+
+            if scripts.declared_functions[p].contains(name.as_str()) {
+                function
+                    .overrides
+                    .get_or_insert_with(|| format!("{}.{}", scripts.names[p], name));
+            }
+            // Undocumented overrides inherit the documentation of the overridden method
+            if let Some((parent_text, parent_doc, parent_tags)) =
+                scripts.functions[p].get(name.as_str())
+            {
+                if text.is_empty() && tags.is_empty() {
+                    text.clone_from(parent_text);
+                    *doc_comment = *parent_doc;
+                    tags.clone_from(parent_tags);
+                }
+                if !text.is_empty() || !tags.is_empty() {
+                    break;
+                }
+            }
+            ancestor = scripts.parents[p];
+        }
+    }
+}
+
+fn collect_inherited(
+    scripts: &Scripts,
+    mut parent: Option<usize>,
+    own_members: Vec<InheritedEntry>,
+) -> Vec<InheritedMembers> {
+    let mut defined: HashSet<(EntryType, String)> = own_members
+        .into_iter()
+        .flat_map(|e| {
+            let entry_type = e.entry_type;
+            e.names.into_iter().map(move |n| (entry_type, n))
+        })
+        .collect();
+    let mut visited = HashSet::new();
+    let mut inherited = Vec::new();
+
+    while let Some(p) = parent {
+        if !visited.insert(p) {
+            break;
+        }
+
+        let entries = scripts.members[p]
+            .iter()
+            .map(|e| InheritedEntry {
+                entry_type: e.entry_type,
+                names: e
+                    .names
+                    .iter()
+                    .filter(|n| defined.insert((e.entry_type, n.to_string())))
+                    .cloned()
+                    .collect(),
+            })
+            .filter(|e| !e.names.is_empty())
+            .collect::<Vec<_>>();
+
+        if !entries.is_empty() {
+            inherited.push(InheritedMembers {
+                ancestor: scripts.names[p].clone(),
+                entries,
+            });
+        }
+        parent = scripts.parents[p];
+    }
+
+    inherited
+}
+
+// Marks the methods of a class and returns the members it inherits, after doing the same for
+// the inner classes declared in it
+fn resolve_class(
+    scripts: &Scripts,
+    resource_path: &str,
+    extends: &Option<String>,
+    parent: Option<usize>,
+    entries: &mut [DocumentationEntry],
+) -> Vec<InheritedMembers> {
+    for symbol in entries.iter_mut().flat_map(|e| e.symbols.iter_mut()) {
+        if let Some(SymbolArgs::ClassArgs(class)) = &mut symbol.arg {
+            let inner_parent = scripts.resolve(&class.extends, resource_path);
+            class.inherited = resolve_class(
+                scripts,
+                resource_path,
+                &class.extends,
+                inner_parent,
+                &mut class.entries,
+            );
+        }
+    }
+
+    let engine_class = scripts.engine_class(extends, parent);
+    mark_overrides(scripts, parent, &engine_class, entries);
+    collect_inherited(scripts, parent, member_names(entries))
+}
+
+/// Resolves the `extends` clauses of all scripts and inner classes against the scripts of the
+/// project. Records the members each class inherits from project-defined ancestors and marks
+/// methods overriding a method of an ancestor or implementing an engine callback.
+pub fn resolve_inheritance(files: &mut [ProjectFile]) {
+    let mut scripts = Scripts {
+        class_names: HashMap::new(),
+        resource_paths: HashMap::new(),
+        names: files.iter().map(|f| f.display_name()).collect(),
+        extends: files.iter().map(|f| f.data.extends.clone()).collect(),
+        parents: Vec::new(),
+        members: files
+            .iter()
+            .map(|f| member_names(&f.data.entries))
+            .collect(),
+        declared_functions: files
+            .iter()
+            .map(|f| f.data.declared_functions.iter().cloned().collect())
+            .collect(),
+        functions: files
+            .iter()
+            .map(|f| function_docs(&f.data.entries))
+            .collect(),
+    };
+    for (i, file) in files.iter().enumerate() {
+        if let Some(class_name) = &file.data.class_name {
+            scripts.class_names.insert(class_name.clone(), i);
+        }
+        scripts.resource_paths.insert(file.resource_path.clone(), i);
+    }
+    scripts.parents = files
+        .iter()
+        .map(|f| scripts.resolve(&f.data.extends, &f.resource_path))
+        .collect();
+
+    for (file, parent) in files.iter_mut().zip(&scripts.parents) {
+        file.data.inherited = resolve_class(
+            &scripts,
+            &file.resource_path,
+            &file.data.extends,
+            *parent,
+            &mut file.data.entries,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::project_files;
+    use crate::parser::{ClassArgStruct, FunctionArgStruct};
+
+    fn project(scripts: &[(&str, &str)]) -> Vec<ProjectFile> {
+        let mut files = project_files(scripts);
+        resolve_inheritance(&mut files);
+        files
+    }
+
+    fn function<'a>(entries: &'a [DocumentationEntry], name: &str) -> &'a FunctionArgStruct {
+        let symbol = entries
+            .iter()
+            .flat_map(|e| e.symbols.iter())
+            .find(|s| s.name == name)
+            .unwrap();
+        match &symbol.arg {
+            Some(SymbolArgs::FunctionArgs(function)) => function,
+            _ => panic!("{} is not a function", name),
+        }
+    }
+
+    fn class<'a>(entries: &'a [DocumentationEntry], name: &str) -> &'a ClassArgStruct {
+        let symbol = entries
+            .iter()
+            .flat_map(|e| e.symbols.iter())
+            .find(|s| s.name == name)
+            .unwrap();
+        match &symbol.arg {
+            Some(SymbolArgs::ClassArgs(class)) => class,
+            _ => panic!("{} is not a class", name),
+        }
+    }
+
+    fn engine_virtuals(extends: &str) -> Vec<&'static str> {
+        let source = format!(
+            "{}\nfunc _init():\n\tpass\nfunc _ready():\n\tpass\nfunc _draw():\n\tpass\n\
+             func _gui_input(event):\n\tpass\n",
+            extends
+        );
+        let files = project(&[("a.gd", &source)]);
+        ["_init", "_ready", "_draw", "_gui_input"]
+            .iter()
+            .copied()
+            .filter(|name| function(&files[0].data.entries, name).engine_virtual)
+            .collect()
+    }
+
+    #[test]
+    fn engine_callbacks_by_base_class() {
+        assert_eq!(engine_virtuals(""), ["_init"]);
+        assert_eq!(engine_virtuals("extends Node"), ["_init", "_ready"]);
+        assert_eq!(
+            engine_virtuals("extends Node2D"),
+            ["_init", "_ready", "_draw"]
+        );
+        assert_eq!(
+            engine_virtuals("extends Button"),
+            ["_init", "_ready", "_draw", "_gui_input"]
+        );
+        // Classes the table doesn't know might declare any of the callbacks
+        assert_eq!(
+            engine_virtuals("extends GPUParticles2D"),
+            ["_init", "_ready", "_draw", "_gui_input"]
+        );
+    }
+
+    #[test]
+    fn engine_class_of_project_scripts() {
+        let files = project(&[
+            ("base.gd", "extends Control\n"),
+            (
+                "ui.gd",
+                "extends \"base.gd\"\nfunc _gui_input(event):\n\tpass\n\
+                 class Inner:\n\tfunc _gui_input(event):\n\t\tpass\n",
+            ),
+        ]);
+        let entries = &files[1].data.entries;
+        assert!(function(entries, "_gui_input").engine_virtual);
+        let inner = class(entries, "Inner");
+        assert!(!function(&inner.entries, "_gui_input").engine_virtual);
+    }
+
+    #[test]
+    fn overrides_of_hidden_methods() {
+        let files = project(&[
+            (
+                "base.gd",
+                "class_name Base\n# [Hide]\nfunc _private():\n\tpass\n## Jumps\nfunc jump():\n\tpass\n",
+            ),
+            (
+                "derived.gd",
+                "extends Base\nfunc _private():\n\tpass\nfunc jump():\n\tpass\n",
+            ),
+        ]);
+        let entries = &files[1].data.entries;
+        assert_eq!(
+            function(entries, "_private").overrides.as_deref(),
+            Some("Base._private")
+        );
+        assert_eq!(
+            function(entries, "jump").overrides.as_deref(),
+            Some("Base.jump")
+        );
+
+        // Only the documentation of listed methods is inherited
+        let text = |name: &str| {
+            let symbol = entries
+                .iter()
+                .flat_map(|e| &e.symbols)
+                .find(|s| s.name == name);
+            symbol.unwrap().text.clone()
+        };
+        assert!(text("_private").is_empty());
+        assert_eq!(text("jump"), ["Jumps"]);
+    }
+
+    #[test]
+    fn nested_inner_classes() {
+        let files = project(&[
+            ("base.gd", "class_name Base\nvar speed\nfunc f():\n\tpass\n"),
+            (
+                "a.gd",
+                "class Outer:\n\tclass Middle:\n\t\tclass Deep extends Base:\n\
+                 \t\t\tfunc f():\n\t\t\t\tpass\n",
+            ),
+        ]);
+        let outer = class(&files[1].data.entries, "Outer");
+        let middle = class(&outer.entries, "Middle");
+        let deep = class(&middle.entries, "Deep");
+
+        assert_eq!(
+            function(&deep.entries, "f").overrides.as_deref(),
+            Some("Base.f")
+        );
+        let ancestors = deep
+            .inherited
+            .iter()
+            .map(|i| i.ancestor.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(ancestors, ["Base"]);
+        assert_eq!(deep.inherited[0].entries[0].names, ["speed"]);
+        assert!(middle.inherited.is_empty());
+    }
+
+    #[test]
+    fn inherited_members() {
+        let files = project(&[
+            (
+                "base.gd",
+                "class_name Base\nvar speed\nfunc jump():\n\tpass\n",
+            ),
+            ("sub/mid.gd", "extends Base\nvar speed\nsignal hit\n"),
+            ("sub/leaf.gd", "extends \"mid.gd\"\n"),
+            (
+                "other.gd",
+                "extends \"res://sub/mid.gd\"\nfunc jump():\n\tpass\n",
+            ),
+        ]);
+        let inherited = |i: usize| {
+            files[i]
+                .data
+                .inherited
+                .iter()
+                .map(|members| {
+                    let names = members
+                        .entries
+                        .iter()
+                        .flat_map(|e| &e.names)
+                        .map(String::as_str)
+                        .collect::<Vec<_>>();
+                    (members.ancestor.as_str(), names)
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert!(inherited(0).is_empty());
+        assert_eq!(inherited(1), [("Base", vec!["jump"])]);
+        // Members are listed under the closest ancestor declaring them
+        assert_eq!(
+            inherited(2),
+            [
+                ("\"res://sub/mid.gd\"", vec!["hit", "speed"]),
+                ("Base", vec!["jump"])
+            ]
+        );
+        assert_eq!(
+            inherited(3),
+            [("\"res://sub/mid.gd\"", vec!["hit", "speed"])]
+        );
+    }
+
+    #[test]
+    fn git_revisions() {
+        let dir = std::env::temp_dir().join(format!("godotdoc-git-{}", std::process::id()));
+        let git_dir = dir.join(".git");
+        std::fs::create_dir_all(git_dir.join("refs/heads")).unwrap();
+        std::fs::create_dir_all(dir.join("game/scripts")).unwrap();
+
+        std::fs::write(git_dir.join("HEAD"), "ref: refs/heads/main\n").unwrap();
+        std::fs::write(git_dir.join("refs/heads/main"), "1a2b3c\n").unwrap();
+        assert_eq!(
+            git_checkout(&dir.join("game/scripts")),
+            Some(("game/scripts/".to_string(), Some("1a2b3c".to_string())))
+        );
+
+        // Branches may only be listed in packed-refs
+        std::fs::remove_file(git_dir.join("refs/heads/main")).unwrap();
+        std::fs::write(
+            git_dir.join("packed-refs"),
+            "# pack-refs with: peeled fully-peeled sorted\n4d5e6f refs/heads/main\n",
+        )
+        .unwrap();
+        assert_eq!(git_head(&git_dir).as_deref(), Some("4d5e6f"));
+
+        std::fs::write(git_dir.join("HEAD"), "7a8b9c\n").unwrap();
+        assert_eq!(git_head(&git_dir).as_deref(), Some("7a8b9c"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}